@@ -0,0 +1,521 @@
+use proc_macro2::TokenStream;
+use syn::{Attribute, Error, Lit, Meta, NestedMeta, Path, Result};
+
+use crate::quote::{quote, ToTokens};
+
+/// Case conversion applied by `#[serde(rename_all = "...")]`.
+#[derive(Debug, Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn from_lit(lit: &Lit) -> Result<Self> {
+        match get_lit_str("rename_all", lit)?.as_str() {
+            "lowercase" => Ok(RenameRule::Lower),
+            "UPPERCASE" => Ok(RenameRule::Upper),
+            "PascalCase" => Ok(RenameRule::Pascal),
+            "camelCase" => Ok(RenameRule::Camel),
+            "snake_case" => Ok(RenameRule::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnake),
+            "kebab-case" => Ok(RenameRule::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(RenameRule::ScreamingKebab),
+            rule => Err(Error::new_spanned(
+                lit,
+                format!("unknown rename rule `{}`", rule),
+            )),
+        }
+    }
+
+    /// Renames a snake_case struct field, as serde does.
+    pub fn apply_to_field(self, field: &str) -> String {
+        match self {
+            RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+            RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+            RenameRule::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            RenameRule::Camel => lowercase_first_char(&RenameRule::Pascal.apply_to_field(field)),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    /// Renames a PascalCase enum variant, as serde does.
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Pascal => variant.to_owned(),
+            RenameRule::Lower => variant.to_ascii_lowercase(),
+            RenameRule::Upper => variant.to_ascii_uppercase(),
+            RenameRule::Camel => lowercase_first_char(variant),
+            RenameRule::Snake => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            RenameRule::ScreamingSnake => RenameRule::Snake
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            RenameRule::Kebab => RenameRule::Snake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+            RenameRule::ScreamingKebab => RenameRule::ScreamingSnake
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+}
+
+/// Lowercases the first character, which is not always one byte long.
+fn lowercase_first_char(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Options set on the struct or enum itself with `#[serde(...)]` or `#[swagger(...)]`.
+#[derive(Debug, Default)]
+pub struct ContainerAttributes {
+    /// The schema is a component, referenced with `$ref` by the schemas using the type.
+    pub component: bool,
+    /// The component name set with `#[serde(rename = "...")]`, which schemars uses too.
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+    pub default: bool,
+    pub tag: Option<String>,
+    pub content: Option<String>,
+    pub untagged: bool,
+}
+
+/// Options set on an enum variant with `#[serde(...)]`.
+#[derive(Debug, Default)]
+pub struct VariantAttributes {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameRule>,
+    pub skip: bool,
+}
+
+/// Where a field is sent, set with `#[swagger(in = "...")]`.
+#[derive(Debug, Clone, Copy)]
+pub enum ParameterLocation {
+    Query,
+    Header,
+    Path,
+    Cookie,
+}
+
+impl ParameterLocation {
+    fn from_lit(lit: &Lit) -> Result<Self> {
+        match get_lit_str("in", lit)?.as_str() {
+            "query" => Ok(ParameterLocation::Query),
+            "header" => Ok(ParameterLocation::Header),
+            "path" => Ok(ParameterLocation::Path),
+            "cookie" => Ok(ParameterLocation::Cookie),
+            location => Err(Error::new_spanned(
+                lit,
+                format!("unknown parameter location `{}`", location),
+            )),
+        }
+    }
+}
+
+/// Options set on a field with `#[swagger(...)]` or `#[serde(...)]`.
+#[derive(Debug, Default)]
+pub struct FieldAttributes {
+    pub description: Option<String>,
+    pub example: Option<TokenStream>,
+    pub format: Option<String>,
+    pub deprecated: bool,
+    pub read_only: bool,
+    pub write_only: bool,
+    pub rename: Option<String>,
+    pub skip: bool,
+    pub default: Option<TokenStream>,
+    /// The field may be missing from the serialized value, see `#[serde(default)]`.
+    pub optional: bool,
+    pub flatten: bool,
+    /// The parameter location in `get_query_definitions`, the query string when unset.
+    pub location: Option<ParameterLocation>,
+    /// JSON Schema validation keywords, like `("minimum", 1)`.
+    pub validation: Vec<(&'static str, TokenStream)>,
+    /// The `#[validate(length(...))]` bounds, which apply to strings and arrays alike.
+    pub length: (Option<TokenStream>, Option<TokenStream>),
+}
+
+fn get_key(path: &Path) -> String {
+    path.get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_else(|| path.to_token_stream().to_string())
+}
+
+fn get_lit_str(key: &str, lit: &Lit) -> Result<String> {
+    match lit {
+        Lit::Str(lit) => Ok(lit.value()),
+        _ => Err(Error::new_spanned(
+            lit,
+            format!("expected `{}` to be a string literal", key),
+        )),
+    }
+}
+
+fn get_lit_bool(key: &str, lit: &Lit) -> Result<bool> {
+    match lit {
+        Lit::Bool(lit) => Ok(lit.value),
+        _ => Err(Error::new_spanned(
+            lit,
+            format!("expected `{}` to be a boolean literal", key),
+        )),
+    }
+}
+
+fn get_lit_number(key: &str, lit: &Lit) -> Result<TokenStream> {
+    match lit {
+        Lit::Int(_) | Lit::Float(_) => Ok(lit.to_token_stream()),
+        _ => Err(Error::new_spanned(
+            lit,
+            format!("expected `{}` to be a number literal", key),
+        )),
+    }
+}
+
+/// Returns the `key(...)` lists found in the `#[<name>(...)]` attributes.
+pub fn get_meta_items(attrs: &[Attribute], name: &str) -> Result<Vec<NestedMeta>> {
+    let mut items = vec![];
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => items.extend(list.nested),
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    format!("expected #[{}(...)]", name),
+                ))
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Returns the value of `key = "..."` or the `serialize = "..."` value of `key(...)`.
+///
+/// serde accepts both forms for `rename` and `rename_all`, only the serialized name is documented.
+fn get_serialize_lit(item: &NestedMeta) -> Option<&Lit> {
+    match item {
+        NestedMeta::Meta(Meta::NameValue(name_value)) => Some(&name_value.lit),
+        NestedMeta::Meta(Meta::List(list)) => list.nested.iter().find_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(name_value))
+                if name_value.path.is_ident("serialize") =>
+            {
+                Some(&name_value.lit)
+            }
+            _ => None,
+        }),
+        _ => None,
+    }
+}
+
+fn get_item_key(item: &NestedMeta) -> Option<String> {
+    match item {
+        NestedMeta::Meta(meta) => Some(get_key(meta.path())),
+        NestedMeta::Lit(_) => None,
+    }
+}
+
+/// Parses the `#[serde(...)]` and `#[swagger(...)]` attributes of a struct or an enum.
+///
+/// serde keys not affecting the schema are ignored, serde itself reports the invalid ones.
+pub fn get_container_attributes(attrs: &[Attribute]) -> Result<ContainerAttributes> {
+    let mut attributes = ContainerAttributes::default();
+
+    for item in get_meta_items(attrs, "serde")? {
+        match get_item_key(&item).as_deref() {
+            Some("rename") => {
+                if let NestedMeta::Meta(Meta::NameValue(name_value)) = &item {
+                    attributes.rename = Some(get_lit_str("rename", &name_value.lit)?);
+                }
+            }
+            Some("rename_all") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.rename_all = Some(RenameRule::from_lit(lit)?);
+                }
+            }
+            Some("default") => attributes.default = true,
+            Some("tag") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.tag = Some(get_lit_str("tag", lit)?);
+                }
+            }
+            Some("content") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.content = Some(get_lit_str("content", lit)?);
+                }
+            }
+            Some("untagged") => attributes.untagged = true,
+            _ => {}
+        }
+    }
+
+    for item in get_meta_items(attrs, "swagger")? {
+        match &item {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("component") => {
+                attributes.component = true
+            }
+            NestedMeta::Meta(meta) => {
+                return Err(Error::new_spanned(
+                    meta.path(),
+                    format!("unknown swagger attribute `{}`", get_key(meta.path())),
+                ))
+            }
+            NestedMeta::Lit(lit) => {
+                return Err(Error::new_spanned(
+                    lit,
+                    "expected a swagger attribute like `key`",
+                ))
+            }
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// Parses the `#[serde(...)]` attributes of an enum variant.
+pub fn get_variant_attributes(attrs: &[Attribute]) -> Result<VariantAttributes> {
+    let mut attributes = VariantAttributes::default();
+
+    for item in get_meta_items(attrs, "serde")? {
+        match get_item_key(&item).as_deref() {
+            Some("rename") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.rename = Some(get_lit_str("rename", lit)?);
+                }
+            }
+            Some("rename_all") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.rename_all = Some(RenameRule::from_lit(lit)?);
+                }
+            }
+            Some("skip") => attributes.skip = true,
+            _ => {}
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// Parses the `#[swagger(...)]` and `#[serde(...)]` attributes of a field.
+///
+/// The `#[swagger(...)]` ones take precedence.
+pub fn get_field_attributes(attrs: &[Attribute]) -> Result<FieldAttributes> {
+    let mut attributes = FieldAttributes::default();
+
+    for item in get_meta_items(attrs, "serde")? {
+        match get_item_key(&item).as_deref() {
+            Some("rename") => {
+                if let Some(lit) = get_serialize_lit(&item) {
+                    attributes.rename = Some(get_lit_str("rename", lit)?);
+                }
+            }
+            Some("skip") => attributes.skip = true,
+            Some("default") | Some("skip_serializing_if") => attributes.optional = true,
+            Some("flatten") => attributes.flatten = true,
+            _ => {}
+        }
+    }
+
+    get_validate_attributes(attrs, &mut attributes);
+
+    for item in get_meta_items(attrs, "swagger")? {
+        match &item {
+            NestedMeta::Meta(Meta::Path(path)) => match get_key(path).as_str() {
+                "deprecated" => attributes.deprecated = true,
+                "unique_items" => attributes.validation.push(("uniqueItems", quote! { true })),
+                "read_only" => attributes.read_only = true,
+                "write_only" => attributes.write_only = true,
+                "skip" => attributes.skip = true,
+                key => {
+                    return Err(Error::new_spanned(
+                        path,
+                        format!("unknown swagger attribute `{}`", key),
+                    ))
+                }
+            },
+            NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                let key = get_key(&name_value.path);
+                let lit = &name_value.lit;
+                match key.as_str() {
+                    "description" => attributes.description = Some(get_lit_str(&key, lit)?),
+                    "example" => attributes.example = Some(lit.to_token_stream()),
+                    "format" => attributes.format = Some(get_lit_str(&key, lit)?),
+                    "deprecated" => attributes.deprecated = get_lit_bool(&key, lit)?,
+                    "read_only" => attributes.read_only = get_lit_bool(&key, lit)?,
+                    "write_only" => attributes.write_only = get_lit_bool(&key, lit)?,
+                    "rename" => attributes.rename = Some(get_lit_str(&key, lit)?),
+                    "skip" => attributes.skip = get_lit_bool(&key, lit)?,
+                    "default" => attributes.default = Some(lit.to_token_stream()),
+                    "in" => attributes.location = Some(ParameterLocation::from_lit(lit)?),
+                    "min" => attributes
+                        .validation
+                        .push(("minimum", get_lit_number(&key, lit)?)),
+                    "max" => attributes
+                        .validation
+                        .push(("maximum", get_lit_number(&key, lit)?)),
+                    "min_length" => attributes
+                        .validation
+                        .push(("minLength", get_lit_number(&key, lit)?)),
+                    "max_length" => attributes
+                        .validation
+                        .push(("maxLength", get_lit_number(&key, lit)?)),
+                    "pattern" => {
+                        let pattern = get_lit_str(&key, lit)?;
+                        attributes.validation.push(("pattern", quote! { #pattern }))
+                    }
+                    "min_items" => attributes
+                        .validation
+                        .push(("minItems", get_lit_number(&key, lit)?)),
+                    "max_items" => attributes
+                        .validation
+                        .push(("maxItems", get_lit_number(&key, lit)?)),
+                    "unique_items" => {
+                        let unique_items = get_lit_bool(&key, lit)?;
+                        attributes
+                            .validation
+                            .push(("uniqueItems", quote! { #unique_items }))
+                    }
+                    "multiple_of" => attributes
+                        .validation
+                        .push(("multipleOf", get_lit_number(&key, lit)?)),
+                    _ => {
+                        return Err(Error::new_spanned(
+                            &name_value.path,
+                            format!("unknown swagger attribute `{}`", key),
+                        ))
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &item,
+                    "expected a swagger attribute like `key` or `key = value`",
+                ))
+            }
+        }
+    }
+
+    Ok(attributes)
+}
+
+/// Reads the constraints of the `validator` crate `#[validate(...)]` attributes.
+///
+/// Validators without a schema counterpart are ignored, as are the attributes that are not
+/// made of literals, e.g. `range(min = MIN)`: the `validator` derive reports the invalid ones.
+fn get_validate_attributes(attrs: &[Attribute], attributes: &mut FieldAttributes) {
+    let items = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("validate"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten();
+
+    for item in items {
+        let bounds = match &item {
+            NestedMeta::Meta(Meta::List(list)) => list
+                .nested
+                .iter()
+                .filter_map(|nested| match nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value)) => match &name_value.lit {
+                        Lit::Int(_) | Lit::Float(_) => {
+                            Some((get_key(&name_value.path), name_value.lit.to_token_stream()))
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        match get_item_key(&item).as_deref() {
+            Some("length") => {
+                for (key, value) in bounds {
+                    match key.as_str() {
+                        "min" => attributes.length.0 = Some(value),
+                        "max" => attributes.length.1 = Some(value),
+                        "equal" => attributes.length = (Some(value.clone()), Some(value)),
+                        _ => {}
+                    }
+                }
+            }
+            Some("range") => {
+                for (key, value) in bounds {
+                    match key.as_str() {
+                        "min" => attributes.validation.push(("minimum", value)),
+                        "max" => attributes.validation.push(("maximum", value)),
+                        "exclusive_min" => {
+                            attributes.validation.push(("minimum", value));
+                            attributes
+                                .validation
+                                .push(("exclusiveMinimum", quote! { true }));
+                        }
+                        "exclusive_max" => {
+                            attributes.validation.push(("maximum", value));
+                            attributes
+                                .validation
+                                .push(("exclusiveMaximum", quote! { true }));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Some("email") => attributes.format = Some("email".to_owned()),
+            Some("url") => attributes.format = Some("uri".to_owned()),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    #[test]
+    fn camel_case() {
+        assert_eq!(
+            RenameRule::Camel.apply_to_field("first_value"),
+            "firstValue"
+        );
+        assert_eq!(RenameRule::Camel.apply_to_field("__"), "");
+        assert_eq!(RenameRule::Camel.apply_to_field("é_x"), "éX");
+        assert_eq!(
+            RenameRule::Camel.apply_to_variant("FirstValue"),
+            "firstValue"
+        );
+        assert_eq!(RenameRule::Camel.apply_to_variant("Éx"), "Éx");
+    }
+}
@@ -1,131 +1,445 @@
-use crate::JsonSchemaDefinition;
+use crate::{JsonSchemaDefinition, ParameterIn, ParameterObject, QueryDefinition};
 
 macro_rules! impl_json_schema_definition {
-    ($rt:ty, "integer", $min:expr, $max:expr) => {
+    ($rt:ty $(as $name:literal)?, "integer", $format:literal, $min:expr, $max:expr) => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
                     "type": "integer",
+                    "format": $format,
                     "minimum": $min,
                     "maximum": $max,
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-    ($rt:ty, "number", $min:expr, $max:expr) => {
+    // the maximum of 64-bit unsigned integers exceeds `int64` and the precision of most JSON parsers
+    ($rt:ty $(as $name:literal)?, "integer", $format:literal, $min:expr) => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
-                    "type": "number",
+                    "type": "integer",
+                    "format": $format,
                     "minimum": $min,
-                    "maximum": $max,
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
+        }
+    };
+    // 128-bit integers overflow most JSON parsers, they are documented as strings
+    ($rt:ty $(as $name:literal)?, "integer", $pattern:literal) => {
+        impl JsonSchemaDefinition for $rt {
+            fn get_json_schema_definition() -> serde_json::Value {
+                json!({
+                    "type": "string",
+                    "pattern": $pattern,
+                })
+            }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
+        }
+    };
+    ($rt:ty $(as $name:literal)?, "number", $format:literal) => {
+        impl JsonSchemaDefinition for $rt {
+            fn get_json_schema_definition() -> serde_json::Value {
+                json!({
+                    "type": "number",
+                    "format": $format,
+                })
+            }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-    ($rt:ty, "boolean") => {
+    ($rt:ty $(as $name:literal)?, "boolean") => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
                     "type": "boolean"
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-    ($rt:ty, "string") => {
+    ($rt:ty $(as $name:literal)?, "string") => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
                     "type": "string",
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-    ($rt:ty, "array", "integer", $min:expr, $max:expr) => {
+    ($rt:ty $(as $name:literal)?, "string", $($keyword:literal: $value:tt),+) => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
-                    "type": "array",
-                    "items": {
-                        "type": "integer",
-                        "minimum": $min,
-                        "maximum": $max,
-                    },
+                    "type": "string",
+                    $($keyword: $value),+
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-    ($rt:ty, "uuid") => {
+    ($rt:ty $(as $name:literal)?, "uuid") => {
         impl JsonSchemaDefinition for $rt {
             fn get_json_schema_definition() -> serde_json::Value {
                 json!({
-                    "type": "uuid",
+                    "type": "string",
+                    "format": "uuid",
                 })
             }
+            $(
+                fn get_component_name() -> String {
+                    $name.to_owned()
+                }
+            )?
         }
     };
-
 }
 impl_json_schema_definition!(uuid::Uuid, "uuid");
-impl_json_schema_definition!(i8, "integer", std::i8::MIN, std::i8::MAX);
-impl_json_schema_definition!(i16, "integer", std::i16::MIN, std::i16::MAX);
-impl_json_schema_definition!(i32, "integer", std::i32::MIN, std::i32::MAX);
-impl_json_schema_definition!(i64, "integer", std::i64::MIN, std::i64::MAX);
-impl_json_schema_definition!(i128, "integer", std::i128::MIN, std::i128::MAX);
-impl_json_schema_definition!(u8, "integer", std::u8::MIN, std::u8::MAX);
-impl_json_schema_definition!(u16, "integer", std::u16::MIN, std::u16::MAX);
-impl_json_schema_definition!(u32, "integer", std::u32::MIN, std::u32::MAX);
-impl_json_schema_definition!(u64, "integer", std::u64::MIN, std::u64::MAX);
-impl_json_schema_definition!(u128, "integer", std::u128::MIN, std::u128::MAX);
-impl_json_schema_definition!(isize, "integer", std::isize::MIN, std::isize::MAX);
-impl_json_schema_definition!(usize, "integer", std::usize::MIN, std::usize::MAX);
-
-impl_json_schema_definition!(f32, "number", std::f32::MIN, std::f32::MAX);
-impl_json_schema_definition!(f64, "number", std::f64::MIN, std::f64::MAX);
-
-impl_json_schema_definition!(bool, "boolean");
+impl_json_schema_definition!(i8 as "int8", "integer", "int32", i8::MIN, i8::MAX);
+impl_json_schema_definition!(i16 as "int16", "integer", "int32", i16::MIN, i16::MAX);
+impl_json_schema_definition!(i32 as "int32", "integer", "int32", i32::MIN, i32::MAX);
+impl_json_schema_definition!(i64 as "int64", "integer", "int64", i64::MIN, i64::MAX);
+impl_json_schema_definition!(i128 as "int128", "integer", "^-?[0-9]+$");
+impl_json_schema_definition!(u8 as "uint8", "integer", "int32", u8::MIN, u8::MAX);
+impl_json_schema_definition!(u16 as "uint16", "integer", "int32", u16::MIN, u16::MAX);
+impl_json_schema_definition!(u32 as "uint32", "integer", "int64", u32::MIN, u32::MAX);
+impl_json_schema_definition!(u64 as "uint64", "integer", "int64", u64::MIN);
+impl_json_schema_definition!(u128 as "uint128", "integer", "^[0-9]+$");
+impl_json_schema_definition!(isize as "int", "integer", "int64", isize::MIN, isize::MAX);
+impl_json_schema_definition!(usize as "uint", "integer", "int64", usize::MIN);
+
+impl_json_schema_definition!(f32 as "float", "number", "float");
+impl_json_schema_definition!(f64 as "double", "number", "double");
+
+impl_json_schema_definition!(bool as "Boolean", "boolean");
 
 impl_json_schema_definition!(String, "string");
-impl_json_schema_definition!(&str, "string");
-
-impl_json_schema_definition!([i8], "array", "integer", std::i8::MIN, std::i8::MAX);
-impl_json_schema_definition!([i16], "array", "integer", std::i16::MIN, std::i16::MAX);
-impl_json_schema_definition!([i32], "array", "integer", std::i32::MIN, std::i32::MAX);
-impl_json_schema_definition!([i64], "array", "integer", std::i64::MIN, std::i64::MAX);
-impl_json_schema_definition!([i128], "array", "integer", std::i128::MIN, std::i128::MAX);
-impl_json_schema_definition!([u8], "array", "integer", std::u8::MIN, std::u8::MAX);
-impl_json_schema_definition!([u16], "array", "integer", std::u16::MIN, std::u16::MAX);
-impl_json_schema_definition!([u32], "array", "integer", std::u32::MIN, std::u32::MAX);
-impl_json_schema_definition!([u64], "array", "integer", std::u64::MIN, std::u64::MAX);
-impl_json_schema_definition!([u128], "array", "integer", std::u128::MIN, std::u128::MAX);
+impl_json_schema_definition!(str as "String", "string");
+
+impl_json_schema_definition!(std::net::Ipv4Addr, "string", "format": "ipv4");
+impl_json_schema_definition!(std::net::Ipv6Addr, "string", "format": "ipv6");
+impl_json_schema_definition!(
+    std::net::IpAddr,
+    "string",
+    "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }]
+);
+impl_json_schema_definition!(std::net::SocketAddrV4, "string", "pattern": r"^[0-9.]+:[0-9]+$");
 impl_json_schema_definition!(
-    [isize],
-    "array",
-    "integer",
-    std::isize::MIN,
-    std::isize::MAX
+    std::net::SocketAddrV6,
+    "string",
+    "pattern": r"^\[[0-9a-fA-F:.%]+\]:[0-9]+$"
 );
 impl_json_schema_definition!(
-    [usize],
-    "array",
-    "integer",
-    std::usize::MIN,
-    std::usize::MAX
+    std::net::SocketAddr,
+    "string",
+    "pattern": r"^([0-9.]+|\[[0-9a-fA-F:.%]+\]):[0-9]+$"
 );
 
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> JsonSchemaDefinition for chrono::DateTime<Tz> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        json!({
+            "type": "string",
+            "format": "date-time",
+        })
+    }
+
+    fn get_component_name() -> String {
+        "DateTime".to_owned()
+    }
+}
+#[cfg(feature = "chrono")]
+impl_json_schema_definition!(chrono::NaiveDate, "string", "format": "date");
+#[cfg(feature = "chrono")]
+impl_json_schema_definition!(chrono::NaiveDateTime, "string", "format": "partial-date-time");
+#[cfg(feature = "chrono")]
+impl_json_schema_definition!(chrono::NaiveTime, "string", "format": "partial-time");
+
+#[cfg(feature = "time")]
+impl_json_schema_definition!(time::OffsetDateTime, "string", "format": "date-time");
+#[cfg(feature = "time")]
+impl_json_schema_definition!(time::Date, "string", "format": "date");
+
+#[cfg(feature = "url")]
+impl_json_schema_definition!(url::Url, "string", "format": "uri");
+
+#[cfg(feature = "ipnet")]
+impl_json_schema_definition!(ipnet::Ipv4Net, "string", "pattern": r"^[0-9.]+/[0-9]{1,2}$");
+#[cfg(feature = "ipnet")]
+impl_json_schema_definition!(ipnet::Ipv6Net, "string", "pattern": r"^[0-9a-fA-F:.]+/[0-9]{1,3}$");
+#[cfg(feature = "ipnet")]
+impl_json_schema_definition!(ipnet::IpNet, "string", "pattern": r"^[0-9a-fA-F:.]+/[0-9]{1,3}$");
+
+// decimals are serialized as strings to keep their precision
+#[cfg(feature = "rust_decimal")]
+impl_json_schema_definition!(
+    rust_decimal::Decimal,
+    "string",
+    "format": "decimal",
+    "pattern": r"^-?[0-9]+(\.[0-9]+)?$"
+);
+#[cfg(feature = "bigdecimal")]
+impl_json_schema_definition!(
+    bigdecimal::BigDecimal,
+    "string",
+    "format": "decimal",
+    "pattern": r"^-?[0-9]+(\.[0-9]+)?([eE][-+]?[0-9]+)?$"
+);
+
+#[cfg(feature = "semver")]
+impl_json_schema_definition!(
+    semver::Version,
+    "string",
+    "pattern": r"^[0-9]+\.[0-9]+\.[0-9]+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$"
+);
+
+#[cfg(feature = "bytes")]
+impl_json_schema_definition!(bytes::Bytes, "string", "format": "byte");
+#[cfg(feature = "bytes")]
+impl_json_schema_definition!(bytes::BytesMut, "string", "format": "byte");
+
 impl<T: JsonSchemaDefinition> JsonSchemaDefinition for Option<T> {
     fn get_json_schema_definition() -> serde_json::Value {
         <T>::get_json_schema_definition()
     }
+
+    fn get_component_name() -> String {
+        format!("Nullable_{}", T::get_component_name())
+    }
 }
 
-impl<T: JsonSchemaDefinition> JsonSchemaDefinition for Vec<T> {
+/// Implements `JsonSchemaDefinition` for the wrappers serialized as the type they wrap.
+macro_rules! impl_transparent_json_schema_definition {
+    ($($wrapper:ty),* $(,)?) => {
+        $(
+            impl<T: JsonSchemaDefinition + ?Sized> JsonSchemaDefinition for $wrapper {
+                fn get_json_schema_definition() -> serde_json::Value {
+                    T::get_json_schema_definition()
+                }
+
+                fn get_component_name() -> String {
+                    T::get_component_name()
+                }
+            }
+        )*
+    };
+}
+
+impl_transparent_json_schema_definition!(
+    &T,
+    Box<T>,
+    std::rc::Rc<T>,
+    std::sync::Arc<T>,
+    std::cell::RefCell<T>,
+    std::sync::Mutex<T>,
+);
+
+impl<T: JsonSchemaDefinition + ToOwned + ?Sized> JsonSchemaDefinition for std::borrow::Cow<'_, T> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        T::get_json_schema_definition()
+    }
+
+    fn get_component_name() -> String {
+        T::get_component_name()
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for [T] {
     fn get_json_schema_definition() -> serde_json::Value {
         json!({
             "type": "array",
             "items": T::get_json_schema_definition(),
         })
     }
+
+    fn get_component_name() -> String {
+        format!("Array_of_{}", T::get_component_name())
+    }
+}
+
+impl<T: JsonSchemaDefinition, const N: usize> JsonSchemaDefinition for [T; N] {
+    fn get_json_schema_definition() -> serde_json::Value {
+        json!({
+            "type": "array",
+            "items": T::get_json_schema_definition(),
+            "minItems": N,
+            "maxItems": N,
+        })
+    }
+
+    fn get_component_name() -> String {
+        format!("Array_size_{}_of_{}", N, T::get_component_name())
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for Vec<T> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        <[T]>::get_json_schema_definition()
+    }
+
+    fn get_component_name() -> String {
+        <[T]>::get_component_name()
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for std::collections::VecDeque<T> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        <[T]>::get_json_schema_definition()
+    }
+
+    fn get_component_name() -> String {
+        <[T]>::get_component_name()
+    }
+}
+
+/// Sets are arrays without duplicates.
+fn get_set_schema<T: JsonSchemaDefinition>() -> serde_json::Value {
+    json!({
+        "type": "array",
+        "items": T::get_json_schema_definition(),
+        "uniqueItems": true,
+    })
+}
+
+impl<T: JsonSchemaDefinition, S> JsonSchemaDefinition for std::collections::HashSet<T, S> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        get_set_schema::<T>()
+    }
+
+    fn get_component_name() -> String {
+        format!("Set_of_{}", T::get_component_name())
+    }
+}
+
+impl<T: JsonSchemaDefinition> JsonSchemaDefinition for std::collections::BTreeSet<T> {
+    fn get_json_schema_definition() -> serde_json::Value {
+        get_set_schema::<T>()
+    }
+
+    fn get_component_name() -> String {
+        format!("Set_of_{}", T::get_component_name())
+    }
+}
+
+/// Returns the schema of the objects keyed by `K`, with `V` values.
+///
+/// Object keys are always strings, so the key constraints are documented with `propertyNames`:
+/// integers as a pattern, enums as the list of their values.
+fn get_map_schema<K: JsonSchemaDefinition + ?Sized, V: JsonSchemaDefinition + ?Sized>(
+) -> serde_json::Value {
+    let mut schema = json!({
+        "type": "object",
+        "additionalProperties": V::get_json_schema_definition(),
+    });
+    if let Some(property_names) = get_property_names(K::get_json_schema_definition()) {
+        schema["propertyNames"] = property_names;
+    }
+    schema
+}
+
+fn get_property_names(key: serde_json::Value) -> Option<serde_json::Value> {
+    if let Some(values) = key.get("enum") {
+        let values = values.as_array()?.iter().map(|value| match value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+        });
+        return Some(json!({ "type": "string", "enum": values.collect::<Vec<_>>() }));
+    }
+    match key["type"].as_str()? {
+        "integer"
+            if key["minimum"]
+                .as_f64()
+                .is_some_and(|minimum| minimum >= 0.0) =>
+        {
+            Some(json!({ "type": "string", "pattern": "^[0-9]+$" }))
+        }
+        "integer" => Some(json!({ "type": "string", "pattern": "^-?[0-9]+$" })),
+        "string" if key.as_object()?.len() > 1 => Some(key),
+        _ => None,
+    }
+}
+
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition, S> JsonSchemaDefinition
+    for std::collections::HashMap<K, V, S>
+{
+    fn get_json_schema_definition() -> serde_json::Value {
+        get_map_schema::<K, V>()
+    }
+
+    fn get_component_name() -> String {
+        format!("Map_of_{}", V::get_component_name())
+    }
+}
+
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition> JsonSchemaDefinition
+    for std::collections::BTreeMap<K, V>
+{
+    fn get_json_schema_definition() -> serde_json::Value {
+        get_map_schema::<K, V>()
+    }
+
+    fn get_component_name() -> String {
+        format!("Map_of_{}", V::get_component_name())
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K: JsonSchemaDefinition, V: JsonSchemaDefinition, S> JsonSchemaDefinition
+    for indexmap::IndexMap<K, V, S>
+{
+    fn get_json_schema_definition() -> serde_json::Value {
+        get_map_schema::<K, V>()
+    }
+
+    fn get_component_name() -> String {
+        format!("Map_of_{}", V::get_component_name())
+    }
+}
+
+impl<T: QueryDefinition> QueryDefinition for Option<T> {
+    fn get_query_definitions() -> Vec<ParameterObject> {
+        T::get_query_definitions()
+            .into_iter()
+            .map(|parameter| match parameter.where_in {
+                ParameterIn::Path => parameter,
+                _ => ParameterObject {
+                    required: Some(false),
+                    ..parameter
+                },
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -152,6 +466,7 @@ mod tests {
             u8,
             json!({
                 "type": "integer",
+                "format": "int32",
                 "minimum": 0,
                 "maximum": 255,
             })
@@ -164,20 +479,71 @@ mod tests {
             i8,
             json!({
                 "type": "integer",
+                "format": "int32",
                 "minimum": -128,
                 "maximum": 127,
             })
         );
     }
 
+    #[test]
+    fn get_json_schema_definition_int64() {
+        test!(
+            u32,
+            json!({
+                "type": "integer",
+                "format": "int64",
+                "minimum": 0,
+                "maximum": 4_294_967_295u32,
+            })
+        );
+        test!(
+            i64,
+            json!({
+                "type": "integer",
+                "format": "int64",
+                "minimum": i64::MIN,
+                "maximum": i64::MAX,
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_unbounded_uint64() {
+        let expected = json!({
+            "type": "integer",
+            "format": "int64",
+            "minimum": 0,
+        });
+        test!(u64, expected);
+        test!(usize, expected);
+    }
+
+    #[test]
+    fn get_json_schema_definition_128_bit_integers() {
+        test!(
+            i128,
+            json!({
+                "type": "string",
+                "pattern": "^-?[0-9]+$",
+            })
+        );
+        test!(
+            u128,
+            json!({
+                "type": "string",
+                "pattern": "^[0-9]+$",
+            })
+        );
+    }
+
     #[test]
     fn get_json_schema_definition_f32() {
         test!(
             f32,
             json!({
                 "type": "number",
-                "minimum": -340_282_346_638_528_860_000_000_000_000_000_000_000.0,
-                "maximum": 340_282_346_638_528_860_000_000_000_000_000_000_000.0,
+                "format": "float",
             })
         );
     }
@@ -188,8 +554,7 @@ mod tests {
             f64,
             json!({
                 "type": "number",
-                "minimum": -179_769_313_486_231_570_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000.0,
-                "maximum": 179_769_313_486_231_570_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000_000.0,
+                "format": "double",
             })
         );
     }
@@ -233,4 +598,245 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn get_json_schema_definition_hash_map() {
+        test!(
+            std::collections::HashMap<String, Vec<bool>>,
+            json!({
+                "type": "object",
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "boolean" },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_btree_map_integer_keys() {
+        test!(
+            std::collections::BTreeMap<u32, String>,
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "propertyNames": { "type": "string", "pattern": "^[0-9]+$" },
+            })
+        );
+        test!(
+            std::collections::BTreeMap<i64, String>,
+            json!({
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+                "propertyNames": { "type": "string", "pattern": "^-?[0-9]+$" },
+            })
+        );
+    }
+
+    #[test]
+    fn get_property_names_enum() {
+        assert_eq!(
+            get_property_names(json!({ "type": "string", "enum": ["Red", "Green"] })),
+            Some(json!({ "type": "string", "enum": ["Red", "Green"] }))
+        );
+        assert_eq!(
+            get_property_names(json!({ "type": "integer", "enum": [1, 2] })),
+            Some(json!({ "type": "string", "enum": ["1", "2"] }))
+        );
+        assert_eq!(get_property_names(json!({ "type": "string" })), None);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn get_json_schema_definition_index_map() {
+        test!(
+            indexmap::IndexMap<String, u8>,
+            json!({
+                "type": "object",
+                "additionalProperties": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "format": "int32",
+                    "maximum": 255,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_slice() {
+        test!(
+            [u8],
+            json!({
+                "type": "array",
+                "items": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "format": "int32",
+                    "maximum": 255,
+                },
+            })
+        );
+        test!(
+            Box<[String]>,
+            json!({
+                "type": "array",
+                "items": { "type": "string" },
+            })
+        );
+        test!(
+            std::collections::VecDeque<bool>,
+            json!({
+                "type": "array",
+                "items": { "type": "boolean" },
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_fixed_size_array() {
+        test!(
+            [String; 3],
+            json!({
+                "type": "array",
+                "items": { "type": "string" },
+                "minItems": 3,
+                "maxItems": 3,
+            })
+        );
+    }
+
+    #[test]
+    fn get_json_schema_definition_sets() {
+        let expected = json!({
+            "type": "array",
+            "items": { "type": "string" },
+            "uniqueItems": true,
+        });
+        test!(std::collections::HashSet<String>, expected);
+        test!(std::collections::BTreeSet<String>, expected);
+    }
+
+    #[test]
+    fn get_json_schema_definition_wrappers() {
+        let expected = json!({ "type": "string" });
+        test!(Box<String>, expected);
+        test!(std::rc::Rc<str>, expected);
+        test!(std::sync::Arc<String>, expected);
+        test!(std::borrow::Cow<'static, str>, expected);
+        test!(std::cell::RefCell<String>, expected);
+        test!(std::sync::Mutex<String>, expected);
+        test!(&&str, expected);
+        assert_eq!(<Box<Vec<u8>>>::get_component_name(), "Array_of_uint8");
+    }
+
+    #[test]
+    fn get_json_schema_definition_ip_addresses() {
+        test!(
+            std::net::Ipv6Addr,
+            json!({ "type": "string", "format": "ipv6" })
+        );
+        test!(
+            std::net::IpAddr,
+            json!({
+                "type": "string",
+                "anyOf": [{ "format": "ipv4" }, { "format": "ipv6" }],
+            })
+        );
+        test!(
+            std::net::SocketAddr,
+            json!({
+                "type": "string",
+                "pattern": r"^([0-9.]+|\[[0-9a-fA-F:.%]+\]):[0-9]+$",
+            })
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn get_json_schema_definition_chrono() {
+        test!(
+            chrono::DateTime<chrono::Utc>,
+            json!({ "type": "string", "format": "date-time" })
+        );
+        test!(
+            chrono::NaiveDate,
+            json!({ "type": "string", "format": "date" })
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn get_json_schema_definition_time() {
+        test!(
+            time::OffsetDateTime,
+            json!({ "type": "string", "format": "date-time" })
+        );
+        test!(time::Date, json!({ "type": "string", "format": "date" }));
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn get_json_schema_definition_url() {
+        test!(url::Url, json!({ "type": "string", "format": "uri" }));
+    }
+
+    #[cfg(feature = "ipnet")]
+    #[test]
+    fn get_json_schema_definition_ipnet() {
+        test!(
+            ipnet::Ipv4Net,
+            json!({ "type": "string", "pattern": r"^[0-9.]+/[0-9]{1,2}$" })
+        );
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn get_json_schema_definition_decimal() {
+        test!(
+            rust_decimal::Decimal,
+            json!({
+                "type": "string",
+                "format": "decimal",
+                "pattern": r"^-?[0-9]+(\.[0-9]+)?$",
+            })
+        );
+        assert_eq!(rust_decimal::Decimal::get_component_name(), "Decimal");
+    }
+
+    #[cfg(feature = "bigdecimal")]
+    #[test]
+    fn get_json_schema_definition_big_decimal() {
+        test!(
+            bigdecimal::BigDecimal,
+            json!({
+                "type": "string",
+                "format": "decimal",
+                "pattern": r"^-?[0-9]+(\.[0-9]+)?([eE][-+]?[0-9]+)?$",
+            })
+        );
+        assert_eq!(bigdecimal::BigDecimal::get_component_name(), "BigDecimal");
+    }
+
+    #[cfg(feature = "semver")]
+    #[test]
+    fn get_json_schema_definition_semver() {
+        test!(
+            semver::Version,
+            json!({
+                "type": "string",
+                "pattern": r"^[0-9]+\.[0-9]+\.[0-9]+(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$",
+            })
+        );
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn get_json_schema_definition_bytes() {
+        test!(bytes::Bytes, json!({ "type": "string", "format": "byte" }));
+        test!(
+            bytes::BytesMut,
+            json!({ "type": "string", "format": "byte" })
+        );
+    }
 }
@@ -1,60 +1,131 @@
 use crate::quote::ToTokens;
-use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
-use std::iter::FromIterator;
-use syn::{Data, DeriveInput, Fields, Variant};
-
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::{
+    parse_quote, Attribute, Data, DataEnum, DeriveInput, Fields, FieldsNamed, FieldsUnnamed,
+    GenericArgument, Generics, Lit, Meta, PathArguments, Type, Variant,
+};
+
+use crate::attributes::{
+    get_container_attributes, get_field_attributes, get_variant_attributes, ContainerAttributes,
+    ParameterLocation,
+};
 use crate::Field;
 
-fn get_fields(ast: &DeriveInput) -> Vec<Field> {
-    match &ast.data {
-        Data::Struct(s) => match &s.fields {
-            Fields::Named(named_fields) => {
-                let mut fields = vec![];
-
-                for field in named_fields.named.iter() {
-                    let field_name: String = field.ident.as_ref().unwrap().to_string().to_owned();
-                    let mut token_stream = TokenStream::new();
-                    field.ty.to_tokens(&mut token_stream);
-                    let field_tokens: Vec<TokenTree> = token_stream.into_iter().collect();
-
-                    fields.push(Field {
-                        name: field_name,
-                        ty: field_tokens,
-                    });
-                }
-                fields
-            }
-            _ => unimplemented!("Only named struct & Enum is implemented. Please send PR!"),
-        },
-        // Enum support?
-        Data::Enum(e) => {
-            let mut fields = vec![];
-            let mut idents = vec![];
-            for variant in e.variants.iter() {
-                match &variant.fields {
-                    Fields::Named(named_fields) => {}
-                    Fields::Unnamed(unnamed_fields) => {
-                        for field in unnamed_fields.unnamed.iter() {
-                            let mut token_stream = TokenStream::new();
-                            field.ty.to_tokens(&mut token_stream);
-                            let field_tokens: Vec<TokenTree> = token_stream.into_iter().collect();
-                            println!("unneamed enum ident: {:?}", field_tokens);
-                        }
-                    }
-                    Fields::Unit => {
-                        idents.push(variant.ident.to_string());
+/// Collects the `///` doc comments of an item into a single description.
+///
+/// Every line loses the leading space added by rustdoc and the result is trimmed,
+/// `None` is returned if the item is undocumented.
+fn get_doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                Lit::Str(lit) => Some(lit.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| {
+            line.strip_prefix(' ')
+                .unwrap_or(&line)
+                .trim_end()
+                .to_owned()
+        })
+        .collect();
+
+    let description = lines.join("\n").trim().to_owned();
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+/// Rejects the types which cannot be serialized, like function pointers or trait objects.
+fn check_type(ty: &Type) -> syn::Result<()> {
+    match ty {
+        Type::Array(array) => check_type(&array.elem),
+        Type::Group(group) => check_type(&group.elem),
+        Type::Paren(paren) => check_type(&paren.elem),
+        Type::Reference(reference) => check_type(&reference.elem),
+        Type::Slice(slice) => check_type(&slice.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().try_for_each(check_type),
+        Type::Path(type_path) => {
+            type_path
+                .path
+                .segments
+                .iter()
+                .try_for_each(|segment| match &segment.arguments {
+                    PathArguments::AngleBracketed(arguments) => {
+                        arguments
+                            .args
+                            .iter()
+                            .try_for_each(|argument| match argument {
+                                GenericArgument::Type(ty) => check_type(ty),
+                                _ => Ok(()),
+                            })
                     }
-                }
-            }
-            fields
+                    _ => Ok(()),
+                })
         }
-
-        _ => unimplemented!("Only struct & Enum is implemented. Please send PR!"),
+        Type::BareFn(_)
+        | Type::ImplTrait(_)
+        | Type::Infer(_)
+        | Type::Never(_)
+        | Type::Ptr(_)
+        | Type::TraitObject(_) => Err(syn::Error::new_spanned(
+            ty,
+            "#[derive(Swagger)] does not support this field type",
+        )),
+        _ => Ok(()),
     }
 }
 
-fn get_struct_name(ast: &DeriveInput) -> String {
-    ast.ident.to_string()
+fn get_type_tokens(ty: &Type) -> syn::Result<Vec<TokenTree>> {
+    check_type(ty)?;
+
+    let mut token_stream = TokenStream::new();
+    ty.to_tokens(&mut token_stream);
+    Ok(token_stream.into_iter().collect())
+}
+
+fn get_fields(
+    named_fields: &FieldsNamed,
+    container_attributes: &ContainerAttributes,
+) -> syn::Result<Vec<Field>> {
+    let mut fields = vec![];
+
+    for field in named_fields.named.iter() {
+        let mut attributes = get_field_attributes(&field.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+        attributes.optional |= container_attributes.default;
+
+        let field_name: String = match &attributes.rename {
+            Some(rename) => rename.clone(),
+            None => {
+                let ident = field.ident.as_ref().unwrap().unraw().to_string();
+                match container_attributes.rename_all {
+                    Some(rule) => rule.apply_to_field(&ident),
+                    None => ident,
+                }
+            }
+        };
+
+        fields.push(Field {
+            name: field_name,
+            ty: get_type_tokens(&field.ty)?,
+            description: attributes
+                .description
+                .clone()
+                .or_else(|| get_doc_comment(&field.attrs)),
+            attributes,
+        });
+    }
+    Ok(fields)
 }
 
 fn contains_option(tt: &[TokenTree]) -> bool {
@@ -64,278 +135,631 @@ fn contains_option(tt: &[TokenTree]) -> bool {
     })
 }
 
-fn get_json_schema_definition(
-    fields: &[Field],
-) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
-    let mut tokens = Vec::new();
+/// Whether the type is a `HashMap`, `BTreeMap` or `IndexMap`, optional or not.
+fn is_map(tt: &[TokenTree]) -> bool {
+    let mut generic_types = tt.windows(2).filter_map(|pair| match pair {
+        [TokenTree::Ident(ident), TokenTree::Punct(punct)] if punct.as_char() == '<' => Some(ident),
+        _ => None,
+    });
+    let ty = match generic_types.next() {
+        Some(ident) if ident == "Option" => generic_types.next(),
+        ident => ident,
+    };
+    ty.is_some_and(|ident| ident == "HashMap" || ident == "BTreeMap" || ident == "IndexMap")
+}
 
-    let mut properties = Vec::new();
-    for field in fields {
-        properties.push(TokenTree::Literal(Literal::string(&field.name)));
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
+/// Whether the field is a `#[serde(flatten)]` struct, its fields being merged into the parent.
+///
+/// Flattened maps collect the remaining entries instead, their parameters are not known.
+fn is_flattened_struct(field: &Field) -> bool {
+    field.attributes.flatten && !is_map(&field.ty)
+}
 
-        properties.push(TokenTree::Punct(Punct::new('<', Spacing::Alone)));
+fn is_required(field: &Field) -> bool {
+    !contains_option(&field.ty) && field.attributes.default.is_none() && !field.attributes.optional
+}
 
-        properties.append(&mut field.ty.clone());
+/// Keywords the `#[swagger(...)]` attributes add to the schema of the field type.
+fn get_schema_keywords(field: &Field) -> Vec<TokenStream> {
+    let attributes = &field.attributes;
+    let mut keywords = vec![];
 
-        properties.push(TokenTree::Punct(Punct::new('>', Spacing::Alone)));
+    if let Some(format) = &attributes.format {
+        keywords.push(quote! { "format": #format });
+    }
+    if let Some(default) = &attributes.default {
+        keywords.push(quote! { "default": #default });
+    }
+    if attributes.read_only {
+        keywords.push(quote! { "readOnly": true });
+    }
+    if attributes.write_only {
+        keywords.push(quote! { "writeOnly": true });
+    }
+    for (keyword, value) in &attributes.validation {
+        keywords.push(quote! { #keyword: #value });
+    }
 
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        properties.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
+    keywords
+}
 
-        properties.push(TokenTree::Ident(Ident::new(
-            "get_json_schema_definition",
-            Span::call_site(),
-        )));
+/// Wraps the `schema` expression so the given keywords are merged into it.
+fn merge_keywords(schema: TokenStream, keywords: Vec<TokenStream>) -> TokenStream {
+    if keywords.is_empty() {
+        schema
+    } else {
+        quote! {
+            struct2swagger::merge_schema_keywords(#schema, json!({ #(#keywords),* }))
+        }
+    }
+}
 
-        properties.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            proc_macro2::TokenStream::new(),
-        )));
+/// Generates the schema of `ty` with the given keywords merged into it.
+fn get_type_schema(ty: &[TokenTree], keywords: Vec<TokenStream>) -> TokenStream {
+    let ty: TokenStream = ty.iter().cloned().collect();
+    merge_keywords(quote! { <#ty>::get_json_schema_definition() }, keywords)
+}
 
-        properties.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-    }
-    let properties = proc_macro2::TokenStream::from_iter(properties.into_iter());
+fn get_description_keywords(description: Option<String>) -> Vec<TokenStream> {
+    description
+        .map(|description| quote! { "description": #description })
+        .into_iter()
+        .collect()
+}
 
-    tokens.push(TokenTree::Group(Group::new(Delimiter::Brace, properties)));
-    let tokens = proc_macro2::TokenStream::from_iter(tokens.into_iter());
+/// Generates the expression building the schema of a single field,
+/// decorated with the field documentation and attributes.
+fn get_field_schema(field: &Field) -> TokenStream {
+    let mut keywords = vec![];
 
-    let mut required_properties: Vec<TokenTree> = Vec::new();
-    for field in fields {
-        let has_option = contains_option(&field.ty);
+    if let Some(description) = &field.description {
+        keywords.push(quote! { "description": #description });
+    }
+    if let Some(example) = &field.attributes.example {
+        keywords.push(quote! { "example": #example });
+    }
+    if field.attributes.deprecated {
+        keywords.push(quote! { "deprecated": true });
+    }
+    keywords.extend(get_schema_keywords(field));
 
-        if has_option {
-            continue;
+    get_constrained_schema(field, keywords)
+}
+
+/// Generates the schema of the field type with its length bounds and the given keywords.
+fn get_constrained_schema(field: &Field, keywords: Vec<TokenStream>) -> TokenStream {
+    let ty: TokenStream = field.ty.iter().cloned().collect();
+    let schema = quote! { <#ty>::get_json_schema_definition() };
+    let schema = match &field.attributes.length {
+        (None, None) => schema,
+        (min, max) => {
+            let min = option_tokens(min);
+            let max = option_tokens(max);
+            quote! { struct2swagger::merge_length_keywords(#schema, #min, #max) }
         }
-        required_properties.push(TokenTree::Literal(Literal::string(&field.name)));
-        required_properties.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    };
+    merge_keywords(schema, keywords)
+}
+
+fn option_tokens(value: &Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
     }
-    let required_properties = proc_macro2::TokenStream::from_iter(required_properties.into_iter());
+}
+
+/// Generates the statements merging the `#[serde(flatten)]` fields into `schema`.
+fn get_flattened_schemas(fields: &[Field]) -> TokenStream {
+    fields
+        .iter()
+        .filter(|field| field.attributes.flatten)
+        .map(|field| {
+            let ty: TokenStream = field.ty.iter().cloned().collect();
+            let required = is_required(field);
+            quote! {
+                struct2swagger::flatten_schema(&mut schema, <#ty>::get_json_schema_definition(), #required);
+            }
+        })
+        .collect()
+}
+
+fn get_json_schema_definition(fields: &[Field]) -> (TokenStream, TokenStream) {
+    let fields: Vec<&Field> = fields
+        .iter()
+        .filter(|field| !field.attributes.flatten)
+        .collect();
+
+    let properties = fields.iter().map(|field| {
+        let name = &field.name;
+        let schema = get_field_schema(field);
+        quote! { #name: #schema, }
+    });
+    let tokens = quote! { { #(#properties)* } };
+
+    let required_properties = fields
+        .iter()
+        .filter(|field| is_required(field))
+        .map(|field| {
+            let name = &field.name;
+            quote! { #name, }
+        })
+        .collect();
 
     (required_properties, tokens)
 }
 
-fn get_query_definitions(fields: &[Field]) -> proc_macro2::TokenStream {
-    let mut query: Vec<TokenTree> = Vec::new();
-
-    for field in fields {
-        query.push(TokenTree::Ident(Ident::new(
-            "struct2swagger",
-            Span::call_site(),
-        )));
-        query.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query.push(TokenTree::Ident(Ident::new(
-            "ParameterObject",
-            Span::call_site(),
-        )));
-
-        let mut query_field_description = Vec::<TokenTree>::new();
-
-        query_field_description.push(TokenTree::Ident(Ident::new("name", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Literal(Literal::string(&field.name)));
-        query_field_description.push(TokenTree::Punct(Punct::new('.', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("to_string", Span::call_site())));
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            TokenStream::new(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("where_in", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "struct2swagger",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "ParameterIn",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Query", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "description",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("required", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Some", Span::call_site())));
-        let required = {
-            let mut required = Vec::new();
-            let is_required = if contains_option(&field.ty) {
-                "false"
+/// Generates the expression building the object schema of the given fields.
+fn get_object_schema(fields: &[Field], description: Option<String>) -> TokenStream {
+    let (required_properties, tokens) = get_json_schema_definition(fields);
+    let flattened_schemas = get_flattened_schemas(fields);
+
+    let description = match description {
+        Some(description) => quote! { "description": #description, },
+        None => quote! {},
+    };
+
+    let schema = if required_properties.is_empty() {
+        quote! {
+            json!({
+                "type": "object",
+                #description
+                "properties": #tokens,
+            })
+        }
+    } else {
+        quote! {
+            json!({
+                "type": "object",
+                #description
+                "required": [ #required_properties ],
+                "properties": #tokens,
+            })
+        }
+    };
+
+    if flattened_schemas.is_empty() {
+        schema
+    } else {
+        quote! {
+            {
+                let mut schema = #schema;
+                #flattened_schemas
+                schema
+            }
+        }
+    }
+}
+
+/// Generates the expression building the fixed-length array schema of a tuple.
+fn get_tuple_schema(types: &[Vec<TokenTree>]) -> TokenStream {
+    let items = types.iter().map(|ty| get_type_schema(ty, vec![]));
+    let len = types.len();
+
+    quote! {
+        json!({
+            "type": "array",
+            "items": struct2swagger::tuple_items_schema(vec![ #(#items),* ]),
+            "minItems": #len,
+            "maxItems": #len,
+        })
+    }
+}
+
+fn get_query_definitions(fields: &[Field]) -> TokenStream {
+    let flattened = fields
+        .iter()
+        .filter(|field| is_flattened_struct(field))
+        .map(|field| {
+            let ty: TokenStream = field.ty.iter().cloned().collect();
+            quote! { parameters.extend(<#ty>::get_query_definitions()); }
+        });
+
+    // A flattened map is a free-form parameter, named after the field.
+    let parameters = fields
+        .iter()
+        .filter(|field| !is_flattened_struct(field))
+        .map(|field| {
+            let name = &field.name;
+            let description = match &field.description {
+                Some(description) => quote! { Some(#description.to_string()) },
+                None => quote! { None },
+            };
+            let location = field
+                .attributes
+                .location
+                .unwrap_or(ParameterLocation::Query);
+            // Path parameters are always required by OpenAPI.
+            let required = match location {
+                ParameterLocation::Path => true,
+                _ => is_required(field) && !field.attributes.flatten,
+            };
+            let where_in = match location {
+                ParameterLocation::Query => quote! { struct2swagger::ParameterIn::Query },
+                ParameterLocation::Header => quote! { struct2swagger::ParameterIn::Header },
+                ParameterLocation::Path => quote! { struct2swagger::ParameterIn::Path },
+                ParameterLocation::Cookie => quote! { struct2swagger::ParameterIn::Cookie },
+            };
+            let deprecated = if field.attributes.deprecated {
+                quote! { Some(true) }
             } else {
-                "true"
+                quote! { None }
             };
-            required.push(TokenTree::Ident(Ident::new(is_required, Span::call_site())));
-            proc_macro2::TokenStream::from_iter(required.into_iter())
-        };
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            required,
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "deprecated",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new(
-            "allow_empty_value",
-            Span::call_site(),
-        )));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("None", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
-
-        query_field_description.push(TokenTree::Ident(Ident::new("schema", Span::call_site())));
-        query_field_description.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-        query_field_description.push(TokenTree::Ident(Ident::new("Some", Span::call_site())));
-        let schema_or_ref = {
-            let mut schema_or_ref = Vec::new();
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "struct2swagger",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "swagger_object",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "SchemaObjectOrReferenceObject",
-                Span::call_site(),
-            )));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-            schema_or_ref.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-            schema_or_ref.push(TokenTree::Ident(Ident::new(
-                "SchemaObject",
-                Span::call_site(),
-            )));
-
-            let boxed = {
-                let mut boxed = Vec::new();
-
-                boxed.push(TokenTree::Ident(Ident::new("Box", Span::call_site())));
-                boxed.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-                boxed.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-                boxed.push(TokenTree::Ident(Ident::new("new", Span::call_site())));
-
-                let query_field_definition = {
-                    let mut query_field_definition = Vec::new();
-                    query_field_definition.push(TokenTree::Punct(Punct::new('<', Spacing::Alone)));
-                    query_field_definition.append(&mut field.ty.clone());
-                    query_field_definition.push(TokenTree::Punct(Punct::new('>', Spacing::Alone)));
-                    query_field_definition.push(TokenTree::Punct(Punct::new(':', Spacing::Joint)));
-                    query_field_definition.push(TokenTree::Punct(Punct::new(':', Spacing::Alone)));
-                    query_field_definition.push(TokenTree::Ident(Ident::new(
-                        "get_json_schema_definition",
-                        Span::call_site(),
-                    )));
-                    query_field_definition.push(TokenTree::Group(Group::new(
-                        Delimiter::Parenthesis,
-                        proc_macro2::TokenStream::new(),
-                    )));
-
-                    proc_macro2::TokenStream::from_iter(query_field_definition.into_iter())
-                };
-
-                boxed.push(TokenTree::Group(Group::new(
-                    Delimiter::Parenthesis,
-                    query_field_definition,
-                )));
-
-                proc_macro2::TokenStream::from_iter(boxed.into_iter())
+            let example = match &field.attributes.example {
+                Some(example) => quote! { Some(json!(#example)) },
+                None => quote! { None },
             };
+            let schema = get_constrained_schema(field, get_schema_keywords(field));
+
+            quote! {
+                struct2swagger::ParameterObject {
+                    name: #name.to_string(),
+                    where_in: #where_in,
+                    description: #description,
+                    required: Some(#required),
+                    deprecated: #deprecated,
+                    allow_empty_value: None,
+                    style: Some(#where_in.default_style()),
+                    explode: Some(#where_in.default_style().default_explode()),
+                    example: #example,
+                    schema: Some(struct2swagger::swagger_object::SchemaObjectOrReferenceObject::SchemaObject(
+                        Box::new(#schema),
+                    )),
+                },
+            }
+        });
 
-            schema_or_ref.push(TokenTree::Group(Group::new(Delimiter::Parenthesis, boxed)));
+    let flattened: Vec<TokenStream> = flattened.collect();
+    if flattened.is_empty() {
+        quote! { vec![ #(#parameters)* ] }
+    } else {
+        quote! {
+            let mut parameters = vec![ #(#parameters)* ];
+            #(#flattened)*
+            parameters
+        }
+    }
+}
+
+/// Generates the schema of a newtype, documented as the type it wraps,
+/// or the fixed-length array schema of a tuple.
+fn get_unnamed_schema(
+    unnamed_fields: &FieldsUnnamed,
+    description: Option<String>,
+) -> syn::Result<TokenStream> {
+    let types = unnamed_fields
+        .unnamed
+        .iter()
+        .map(|field| get_type_tokens(&field.ty))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let keywords = get_description_keywords(description);
+
+    if types.len() == 1 {
+        Ok(get_type_schema(&types[0], keywords))
+    } else {
+        Ok(merge_keywords(get_tuple_schema(&types), keywords))
+    }
+}
+
+/// Generates the schema of the data carried by a variant, `None` for unit variants.
+fn get_variant_content(
+    variant: &Variant,
+    container_attributes: ContainerAttributes,
+) -> syn::Result<Option<TokenStream>> {
+    match &variant.fields {
+        Fields::Named(named_fields) => {
+            let fields = get_fields(named_fields, &container_attributes)?;
+            Ok(Some(get_object_schema(&fields, None)))
+        }
+        Fields::Unnamed(unnamed_fields) => Ok(Some(get_unnamed_schema(unnamed_fields, None)?)),
+        Fields::Unit => Ok(None),
+    }
+}
 
-            proc_macro2::TokenStream::from_iter(schema_or_ref.into_iter())
+/// Generates the expression building the schema of an enum.
+///
+/// Every variant becomes an entry of `oneOf`, shaped after the serde representation:
+/// externally tagged by default, internally tagged with `tag`, adjacently tagged with
+/// `tag` and `content`, or `untagged`. An externally tagged enum without data is a string enum.
+/// The variants of the tagged enums are moved to their own components, see `tagged_enum_schema`.
+fn get_enum_schema(
+    data_enum: &DataEnum,
+    container_attributes: &ContainerAttributes,
+    description: Option<String>,
+) -> syn::Result<TokenStream> {
+    let mut unit_variants = vec![];
+    let mut schemas = vec![];
+
+    for variant in data_enum.variants.iter() {
+        let attributes = get_variant_attributes(&variant.attrs)?;
+        if attributes.skip {
+            continue;
+        }
+
+        let name = match &attributes.rename {
+            Some(rename) => rename.clone(),
+            None => {
+                let ident = variant.ident.unraw().to_string();
+                match container_attributes.rename_all {
+                    Some(rule) => rule.apply_to_variant(&ident),
+                    None => ident,
+                }
+            }
+        };
+        let content = get_variant_content(
+            variant,
+            ContainerAttributes {
+                rename_all: attributes.rename_all,
+                ..ContainerAttributes::default()
+            },
+        )?;
+
+        let tag = &container_attributes.tag;
+        let schema = match (content, tag, &container_attributes.content) {
+            (content, _, _) if container_attributes.untagged => match content {
+                Some(content) => content,
+                None => quote! { json!({ "enum": [null], "nullable": true }) },
+            },
+            (None, None, _) => {
+                unit_variants.push(name);
+                continue;
+            }
+            (Some(content), None, _) => quote! {
+                {
+                    let content = #content;
+                    json!({
+                        "type": "object",
+                        "required": [ #name ],
+                        "properties": { #name: content },
+                        "additionalProperties": false,
+                    })
+                }
+            },
+            (None, Some(tag), _) => quote! {
+                json!({
+                    "type": "object",
+                    "required": [ #tag ],
+                    "properties": { #tag: { "type": "string", "enum": [ #name ] } },
+                })
+            },
+            (Some(content), Some(tag), Some(content_name)) => quote! {
+                {
+                    let content = #content;
+                    json!({
+                        "type": "object",
+                        "required": [ #tag, #content_name ],
+                        "properties": {
+                            #tag: { "type": "string", "enum": [ #name ] },
+                            #content_name: content,
+                        },
+                    })
+                }
+            },
+            (Some(content), Some(tag), None) => {
+                if let Fields::Unnamed(unnamed_fields) = &variant.fields {
+                    if unnamed_fields.unnamed.len() > 1 {
+                        return Err(syn::Error::new_spanned(
+                            variant,
+                            "internally tagged enums do not support tuple variants",
+                        ));
+                    }
+                }
+                quote! {
+                    {
+                        let mut schema = json!({
+                            "type": "object",
+                            "required": [ #tag ],
+                            "properties": { #tag: { "type": "string", "enum": [ #name ] } },
+                        });
+                        struct2swagger::flatten_schema(&mut schema, #content, true);
+                        schema
+                    }
+                }
+            }
         };
 
-        query_field_description.push(TokenTree::Group(Group::new(
-            Delimiter::Parenthesis,
-            schema_or_ref,
-        )));
+        schemas.push(merge_keywords(
+            schema,
+            get_description_keywords(get_doc_comment(&variant.attrs)),
+        ));
+    }
 
-        query_field_description.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    let description = match description {
+        Some(description) => quote! { "description": #description, },
+        None => quote! {},
+    };
 
-        let query_field_description =
-            proc_macro2::TokenStream::from_iter(query_field_description.into_iter());
+    if schemas.is_empty() {
+        return Ok(quote! {
+            json!({
+                "type": "string",
+                #description
+                "enum": [ #(#unit_variants),* ],
+            })
+        });
+    }
+    if !unit_variants.is_empty() {
+        schemas.insert(
+            0,
+            quote! { json!({ "type": "string", "enum": [ #(#unit_variants),* ] }) },
+        );
+    }
 
-        query.push(TokenTree::Group(Group::new(
-            Delimiter::Brace,
-            query_field_description,
-        )));
-        query.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+    let schema = quote! {
+        {
+            let one_of: Vec<serde_json::Value> = vec![ #(#schemas),* ];
+            json!({
+                #description
+                "oneOf": one_of,
+            })
+        }
+    };
+    if container_attributes.tag.is_some() && !container_attributes.untagged {
+        Ok(quote! { struct2swagger::tagged_enum_schema::<Self>(#schema) })
+    } else {
+        Ok(schema)
     }
+}
+
+/// Generates the schema and the query parameters of the type,
+/// with the types of the flattened structs which must implement `QueryDefinition`.
+fn get_definitions(ast: &DeriveInput) -> syn::Result<(TokenStream, TokenStream, Vec<TokenStream>)> {
+    let container_attributes = get_container_attributes(&ast.attrs)?;
+    let description = get_doc_comment(&ast.attrs);
+    let mut flattened_types = vec![];
 
-    proc_macro2::TokenStream::from_iter(query.into_iter())
+    let (schema, query_definitions) = match &ast.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(named_fields) => {
+                let fields = get_fields(named_fields, &container_attributes)?;
+                flattened_types = fields
+                    .iter()
+                    .filter(|field| is_flattened_struct(field))
+                    .map(|field| field.ty.iter().cloned().collect())
+                    .collect();
+                (
+                    get_object_schema(&fields, description),
+                    get_query_definitions(&fields),
+                )
+            }
+            Fields::Unnamed(unnamed_fields) => (
+                get_unnamed_schema(unnamed_fields, description)?,
+                quote! { vec![] },
+            ),
+            Fields::Unit => (
+                merge_keywords(
+                    quote! { json!({ "enum": [null], "nullable": true }) },
+                    get_description_keywords(description),
+                ),
+                quote! { vec![] },
+            ),
+        },
+        Data::Enum(data_enum) => (
+            get_enum_schema(data_enum, &container_attributes, description)?,
+            quote! { vec![] },
+        ),
+        Data::Union(data_union) => {
+            return Err(syn::Error::new_spanned(
+                data_union.union_token,
+                "#[derive(Swagger)] does not support unions",
+            ))
+        }
+    };
+
+    let schema = if container_attributes.component {
+        quote! { struct2swagger::component_schema::<Self>(#schema) }
+    } else {
+        schema
+    };
+
+    Ok((schema, query_definitions, flattened_types))
 }
 
-pub fn implements_swagger_trait(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    let ast = syn::parse2(input).unwrap();
-    let struct_name = get_struct_name(&ast);
-    let fields = get_fields(&ast);
+/// Generates the expression naming the component of the type, the way schemars names it.
+///
+/// Generic types are named after their instantiation, like `Page_for_User` for `Page<User>`,
+/// unless `#[serde(rename = "...")]` names them, the `{T}` placeholders being replaced.
+fn get_component_name(ast: &DeriveInput, rename: Option<&str>) -> TokenStream {
+    let type_params: Vec<&Ident> = ast
+        .generics
+        .type_params()
+        .map(|type_param| &type_param.ident)
+        .collect();
+    let const_params: Vec<&Ident> = ast
+        .generics
+        .const_params()
+        .map(|const_param| &const_param.ident)
+        .collect();
+    let params = quote! {
+        #( (stringify!(#type_params), <#type_params as struct2swagger::JsonSchemaDefinition>::get_component_name()), )*
+        #( (stringify!(#const_params), #const_params.to_string()), )*
+    };
+
+    match rename {
+        Some(rename) if rename.contains('{') => quote! {
+            IntoIterator::into_iter([#params]).fold(#rename.to_string(), |name, (param, value)| {
+                name.replace(&format!("{{{}}}", param), &value)
+            })
+        },
+        Some(rename) => quote! { #rename.to_string() },
+        None if type_params.is_empty() && const_params.is_empty() => {
+            let name = ast.ident.unraw().to_string();
+            quote! { #name.to_string() }
+        }
+        None => {
+            let name = ast.ident.unraw().to_string();
+            quote! {
+                format!(
+                    "{}_for_{}",
+                    #name,
+                    IntoIterator::into_iter([#params])
+                        .map(|(_, value)| value)
+                        .collect::<Vec<String>>()
+                        .join("_and_"),
+                )
+            }
+        }
+    }
+}
 
-    let (required_properties, tokens) = get_json_schema_definition(&fields);
+/// Adds `bound` to every type parameter of `generics`.
+fn add_trait_bounds(generics: &Generics, bound: TokenStream) -> Generics {
+    let mut generics = generics.clone();
+    for type_param in generics.type_params_mut() {
+        type_param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
 
-    let query_params = get_query_definitions(&fields);
+pub fn implements_swagger_trait(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ast: DeriveInput = match syn::parse2(input) {
+        Ok(ast) => ast,
+        Err(err) => return err.to_compile_error(),
+    };
+    let (schema, query_params, flattened_types) = match get_definitions(&ast) {
+        Ok(definitions) => definitions,
+        Err(err) => return err.to_compile_error(),
+    };
+    let component_name = match get_container_attributes(&ast.attrs) {
+        Ok(attributes) => get_component_name(&ast, attributes.rename.as_deref()),
+        Err(err) => return err.to_compile_error(),
+    };
 
-    let struct_name_ident = TokenTree::Ident(Ident::new(&struct_name, Span::call_site()));
+    let struct_name_ident = &ast.ident;
+    let generics = add_trait_bounds(
+        &ast.generics,
+        quote! { struct2swagger::JsonSchemaDefinition },
+    );
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut query_generics = generics.clone();
+    if !flattened_types.is_empty() {
+        let where_clause = query_generics.make_where_clause();
+        for ty in flattened_types {
+            where_clause
+                .predicates
+                .push(parse_quote!(#ty: struct2swagger::QueryDefinition));
+        }
+    }
+    let (_, _, query_where_clause) = query_generics.split_for_impl();
 
     let query_definition_quote = quote! {
-        impl struct2swagger::QueryDefinition for #struct_name_ident {
+        impl #impl_generics struct2swagger::QueryDefinition for #struct_name_ident #ty_generics #query_where_clause {
             fn get_query_definitions() -> Vec<struct2swagger::ParameterObject> {
-                vec![
-                    #query_params
-                ]
+                #query_params
             }
         }
     };
-    let json_schema_definition_quote;
-    if required_properties.is_empty() {
-        json_schema_definition_quote = quote! {
-            impl struct2swagger::JsonSchemaDefinition for #struct_name_ident {
-                fn get_json_schema_definition() -> serde_json::Value {
-                    json!({
-                        "type": "object",
-                        "properties": #tokens,
-                    })
-                }
+    let json_schema_definition_quote = quote! {
+        impl #impl_generics struct2swagger::JsonSchemaDefinition for #struct_name_ident #ty_generics #where_clause {
+            fn get_json_schema_definition() -> serde_json::Value {
+                struct2swagger::schema_or_reference::<Self>(|| #schema)
             }
-        }
-    } else {
-        json_schema_definition_quote = quote! {
-            impl struct2swagger::JsonSchemaDefinition for #struct_name_ident {
-                fn get_json_schema_definition() -> serde_json::Value {
-                    json!({
-                        "type": "object",
-                        "required": [ #required_properties ],
-                        "properties": #tokens,
-                    })
-                }
+
+            fn get_component_name() -> String {
+                #component_name
             }
         }
-    }
+    };
 
     quote! {
         #json_schema_definition_quote
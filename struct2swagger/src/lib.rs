@@ -6,134 +6,412 @@ extern crate syn;
 #[macro_use]
 extern crate quote;
 
-#[macro_use]
-extern crate serde_derive;
 #[macro_use]
 extern crate serde_json;
 
+mod attributes;
 mod impl_data_types;
 mod impl_swagger_trait;
+mod openapi_schema;
+mod operation_builder;
 pub mod swagger_object;
 
-use schemars::{gen::SchemaGenerator, schema_for, JsonSchema};
-
 #[derive(Debug)]
 struct Field {
     name: String,
     ty: Vec<proc_macro2::TokenTree>,
+    description: Option<String>,
+    attributes: attributes::FieldAttributes,
 }
 
 pub use impl_swagger_trait::implements_swagger_trait;
-pub use swagger_object::{ParameterIn, ParameterObject, SchemaObjectOrReferenceObject};
+pub use swagger_object::{
+    ParameterIn, ParameterObject, ParameterStyle, SchemaObjectOrReferenceObject,
+};
 
 pub trait JsonSchemaDefinition {
     fn get_json_schema_definition() -> serde_json::Value;
+
+    /// Name of the schema in `components.schemas`.
+    ///
+    /// The name schemars gives to the type, like `Page_for_User` for `Page<User>` or `Array_of_uint8`
+    /// for `Vec<u8>`, so the references match the components registered from schemars schemas.
+    /// Defaults to the type name without module paths, like `User` for `my_crate::User`.
+    fn get_component_name() -> String {
+        get_type_component_name(std::any::type_name::<Self>())
+    }
 }
 pub trait QueryDefinition {
     fn get_query_definitions() -> Vec<ParameterObject>;
+
+    /// The parameters documented as path parameters, for the structs extracting the route
+    /// template parameters.
+    fn get_path_definitions() -> Vec<ParameterObject> {
+        Self::get_query_definitions()
+            .into_iter()
+            .map(|parameter| ParameterObject {
+                where_in: ParameterIn::Path,
+                required: Some(true),
+                style: Some(ParameterIn::Path.default_style()),
+                explode: Some(ParameterIn::Path.default_style().default_explode()),
+                ..parameter
+            })
+            .collect()
+    }
+}
+
+/// Turns a `std::any::type_name` into a component name, dropping the module paths
+/// and joining the type arguments with `_`.
+fn get_type_component_name(type_name: &str) -> String {
+    type_name
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .filter_map(|path| path.rsplit("::").next())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+thread_local! {
+    /// The types whose schema is being built, see `schema_or_reference`.
+    static BUILDING_SCHEMAS: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Builds the schema of `T`, or references its component when the schema of `T` is already
+/// being built, so recursive types like `struct Node { next: Option<Box<Node>> }` end.
+///
+/// Used by `#[derive(Swagger)]`.
+#[doc(hidden)]
+pub fn schema_or_reference<T: JsonSchemaDefinition + ?Sized>(
+    schema: impl FnOnce() -> serde_json::Value,
+) -> serde_json::Value {
+    /// Pops the type even if building its schema panics.
+    struct Building;
+
+    impl Drop for Building {
+        fn drop(&mut self) {
+            BUILDING_SCHEMAS.with(|building| building.borrow_mut().pop());
+        }
+    }
+
+    let type_name = std::any::type_name::<T>();
+    let is_recursive = BUILDING_SCHEMAS.with(|building| {
+        let mut building = building.borrow_mut();
+        let is_recursive = building.contains(&type_name);
+        if !is_recursive {
+            building.push(type_name);
+        }
+        is_recursive
+    });
+    if is_recursive {
+        return json!({ "$ref": format!("#/components/schemas/{}", T::get_component_name()) });
+    }
+
+    let _building = Building;
+    schema()
+}
+
+/// Adds `keywords` to `schema`, overriding the keywords already present.
+///
+/// Used by `#[derive(Swagger)]` to decorate the schema of a field.
+#[doc(hidden)]
+pub fn merge_schema_keywords(
+    mut schema: serde_json::Value,
+    keywords: serde_json::Value,
+) -> serde_json::Value {
+    if let (Some(schema), serde_json::Value::Object(keywords)) = (schema.as_object_mut(), keywords)
+    {
+        schema.extend(keywords);
+    }
+    schema
+}
+
+/// Adds the length bounds of a `#[validate(length(...))]` field to `schema`.
+///
+/// Arrays are bounded with `minItems` and `maxItems`, other values with `minLength` and `maxLength`.
+#[doc(hidden)]
+pub fn merge_length_keywords(
+    mut schema: serde_json::Value,
+    min: Option<u64>,
+    max: Option<u64>,
+) -> serde_json::Value {
+    let (min_keyword, max_keyword) = if schema["type"] == "array" {
+        ("minItems", "maxItems")
+    } else {
+        ("minLength", "maxLength")
+    };
+    if let Some(schema) = schema.as_object_mut() {
+        if let Some(min) = min {
+            schema.insert(min_keyword.to_owned(), min.into());
+        }
+        if let Some(max) = max {
+            schema.insert(max_keyword.to_owned(), max.into());
+        }
+    }
+    schema
+}
+
+/// Merges the properties of a `#[serde(flatten)]` field schema into `schema`.
+///
+/// The flattened `required` properties are kept only if the field itself is required.
+/// A flattened map documents the values of the other properties with `additionalProperties`.
+/// A flattened component is merged from its definition, the other definitions move to `schema`.
+#[doc(hidden)]
+pub fn flatten_schema(
+    schema: &mut serde_json::Value,
+    mut flattened: serde_json::Value,
+    required: bool,
+) {
+    let mut definitions = vec![];
+    openapi_schema::take_definitions(&mut flattened, &mut definitions);
+    if let Some(reference) = flattened.get("$ref").and_then(|r| r.as_str()) {
+        let position = definitions
+            .iter()
+            .position(|(name, _)| reference == format!("#/components/schemas/{}", name));
+        if let Some(position) = position {
+            flattened = definitions.remove(position).1;
+        }
+    }
+    if !definitions.is_empty() {
+        if let Some(schema) = schema.as_object_mut() {
+            let nested = schema.entry("definitions").or_insert_with(|| json!({}));
+            if let Some(nested) = nested.as_object_mut() {
+                nested.extend(definitions);
+            }
+        }
+    }
+
+    let flattened_properties = flattened.get("properties").and_then(|p| p.as_object());
+    if let Some(flattened_properties) = flattened_properties {
+        if let Some(properties) = schema["properties"].as_object_mut() {
+            properties.extend(flattened_properties.clone());
+        }
+    }
+    if let Some(additional_properties) = flattened.get("additionalProperties") {
+        if let Some(schema) = schema.as_object_mut() {
+            schema.insert(
+                "additionalProperties".to_owned(),
+                additional_properties.clone(),
+            );
+        }
+    }
+
+    let flattened_required = flattened.get("required").and_then(|r| r.as_array());
+    if let (true, Some(flattened_required)) = (required, flattened_required) {
+        if !flattened_required.is_empty() {
+            let schema = schema.as_object_mut().unwrap();
+            let required = schema.entry("required").or_insert_with(|| json!([]));
+            if let Some(required) = required.as_array_mut() {
+                required.extend(flattened_required.iter().cloned());
+            }
+        }
+    }
+}
+
+/// Returns the root schema of `T` generated for OpenAPI 3.0, with the schemas it references
+/// in `definitions`.
+///
+/// Used by `swagger_add_router!`, `SwaggerObject::add_schema` and the `OperationBuilder`.
+#[doc(hidden)]
+pub fn get_openapi3_schema<T: schemars::JsonSchema + ?Sized>() -> serde_json::Value {
+    let generator = schemars::gen::SchemaSettings::openapi3().into_generator();
+    json!(generator.into_root_schema_for::<T>())
+}
+
+/// Moves the variants of a tagged enum schema to the `<Enum>_<tag>` components, carried in
+/// `definitions` until the schema is registered, for the discriminator to map the tags to them.
+///
+/// Used by `#[derive(Swagger)]` for the enums with `#[serde(tag = "...")]`.
+#[doc(hidden)]
+pub fn tagged_enum_schema<T: JsonSchemaDefinition + ?Sized>(
+    mut schema: serde_json::Value,
+) -> serde_json::Value {
+    let mut definitions = vec![];
+    openapi_schema::take_tagged_variants(&T::get_component_name(), &mut schema, &mut definitions);
+    if !definitions.is_empty() {
+        schema["definitions"] = definitions
+            .into_iter()
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+    }
+    schema
 }
 
+/// Returns the reference to the component of `T`, carrying its schema, titled after the
+/// component, and the schemas it references in `definitions` until the schema is registered.
+///
+/// Used by `#[derive(Swagger)]` for the types with `#[swagger(component)]`.
+#[doc(hidden)]
+pub fn component_schema<T: JsonSchemaDefinition + ?Sized>(
+    mut schema: serde_json::Value,
+) -> serde_json::Value {
+    let name = T::get_component_name();
+    let mut definitions = vec![];
+    openapi_schema::take_definitions(&mut schema, &mut definitions);
+    if let Some(schema) = schema.as_object_mut() {
+        schema.insert("title".to_owned(), name.clone().into());
+    }
+    definitions.push((name.clone(), schema));
+    json!({
+        "$ref": format!("#/components/schemas/{}", name),
+        "definitions": definitions.into_iter().collect::<serde_json::Map<_, _>>(),
+    })
+}
+
+/// Returns the `items` schema of a tuple, as OpenAPI 3.0 has no schema per position:
+/// the schema shared by all the elements, or `oneOf` their distinct schemas.
+///
+/// Used by `#[derive(Swagger)]`, along with `minItems` and `maxItems`.
+#[doc(hidden)]
+pub fn tuple_items_schema(schemas: Vec<serde_json::Value>) -> serde_json::Value {
+    let mut distinct_schemas: Vec<serde_json::Value> = vec![];
+    for schema in schemas {
+        if !distinct_schemas.contains(&schema) {
+            distinct_schemas.push(schema);
+        }
+    }
+    if distinct_schemas.len() == 1 {
+        distinct_schemas.remove(0)
+    } else {
+        json!({ "oneOf": distinct_schemas })
+    }
+}
+
+/// Documents a route in a `SwaggerObject`.
+///
+/// The method is any `HttpMethod`, case-insensitive, like `"GET"` or `"options"`.
+/// The path parameters, like `{id}`, `{id:u64}`, `{year:[0-9]{4}}` or `:id`, are documented from the path,
+/// or from the fields of a `QueryDefinition` given after `"path_params"`. An annotation which is
+/// neither a known type nor a regular expression, like `{id:u46}`, is a `SchemaError`.
+/// The responses are either a single `status, description, Type` triple or a list like
+/// `[201 => ("Created", User), 204 => ("No content"), default => ("Error", ErrorBody)]`.
+/// The body types may be generic or paths, like `Page<User>` or `models::User`.
+/// The request and response schemas are registered in `components.schemas` on first use.
 #[macro_export]
 macro_rules! swagger_add_router {
-    ($swagger_object:expr, $secure:ident, $tag:literal,  "GET", $path:literal, $query_params: ident, 200, $description: expr, $response:ident) => {{
+    (@responses $($status:tt => ($description:expr $(, $response:ty)?)),* $(,)?) => {
+        vec![$(
+            (
+                $crate::swagger_add_router!(@status $status),
+                ($description, $crate::swagger_add_router!(@body $($response)?)),
+            )
+        ),*]
+    };
+    (@body) => {
+        None
+    };
+    (@body $response:ty) => {{
+        let schema = $crate::get_openapi3_schema::<$response>();
+        Some((schema["title"].as_str().unwrap_or_default().to_owned(), schema))
+    }};
+    (@status default) => {
+        $crate::swagger_object::ResponseStatus::Default
+    };
+    (@status $status:literal) => {
+        $crate::swagger_object::ResponseStatus::Code($status)
+    };
+    (@method $method:literal) => {{
+        const METHOD: $crate::swagger_object::HttpMethod =
+            match $crate::swagger_object::HttpMethod::from_name($method) {
+                Some(method) => method,
+                None => panic!(concat!("unknown HTTP method ", $method)),
+            };
+        METHOD
+    }};
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, "request_body", $req:ty, [$($responses:tt)*]) => {{
+        use struct2swagger::swagger_object::{
+            MediaTypeObject, RequestBodyObject, SchemaObjectOrReferenceObject,
+        };
+        let schema = $crate::get_openapi3_schema::<$req>();
+        let title = schema["title"].as_str().unwrap_or_default().to_owned();
+        let mut content_hash_map = ::std::collections::HashMap::new();
+        content_hash_map.insert(
+            "application/json".to_owned(),
+            MediaTypeObject {
+                schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(schema))),
+                example: None,
+                examples: None,
+                encoding: None,
+            },
+        );
         $swagger_object.add_route(
             $secure,
             $tag,
-            "GET",
+            $crate::swagger_add_router!(@method $method),
             String::from($path),
-            None, // TODO
-            // Some(
-            //     <$query_params>::get_query_definitions()
-            //         .into_iter()
-            //         .map(|p| {
-            //             $crate::swagger_object::ParameterObjectOrReferenceObject::ParameterObject(
-            //                 Box::new(p),
-            //             )
-            //         })
-            //         .collect(),
-            // ),
             None,
-            vec![(
-                200 as u16,
-                // ($description, $response::get_json_schema_definition()),
-                ($description, &json!(&schema_for!($response).schema)["title"].to_string().trim_matches('\"').to_string(), json!(&schema_for!($response).schema)),
-
-            )],
+            Some(
+                (&title,
+                RequestBodyObject {
+                    description: None,
+                    content: content_hash_map,
+                    required: Some(true),
+                })
+            ),
+            $crate::swagger_add_router!(@responses $($responses)*),
         )
-
     }};
-    ($swagger_object:expr, $secure:ident, $tag:literal, "GET", $path:literal, 200, $description: expr, $response:ident) => {{
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, "request_body", $req:ty, $status:tt, $description:expr, $response:ty) => {
+        $crate::swagger_add_router!($swagger_object, $secure, $tag, $method, $path, "request_body", $req, [$status => ($description, $response)])
+    };
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, "path_params", $path_params:ident, [$($responses:tt)*]) => {{
         $swagger_object.add_route(
             $secure,
             $tag,
-            "GET",
+            $crate::swagger_add_router!(@method $method),
             String::from($path),
-            // Check path if we need a ParameterObject
-            None,
+            Some(
+                <$path_params as $crate::QueryDefinition>::get_path_definitions()
+                    .into_iter()
+                    .map(|p| {
+                        $crate::swagger_object::ParameterObjectOrReferenceObject::ParameterObject(
+                            Box::new(p),
+                        )
+                    })
+                    .collect(),
+            ),
             None,
-            vec![(
-                200 as u16,
-                // ($description, $response::get_json_schema_definition()),
-                ($description, &json!(&schema_for!($response).schema)["title"].to_string().trim_matches('\"').to_string(), json!(&schema_for!($response).schema)),
-            )],
+            $crate::swagger_add_router!(@responses $($responses)*),
         )
     }};
-    ($swagger_object:expr, $secure:ident, $tag:literal,  "DELETE", $path:literal, 200, $description: expr, $response:ident) => {{
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, "path_params", $path_params:ident, $status:tt, $description:expr, $response:ty) => {
+        $crate::swagger_add_router!($swagger_object, $secure, $tag, $method, $path, "path_params", $path_params, [$status => ($description, $response)])
+    };
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, $query_params:ident, [$($responses:tt)*]) => {{
         $swagger_object.add_route(
             $secure,
             $tag,
-            "DELETE",
+            $crate::swagger_add_router!(@method $method),
             String::from($path),
+            Some(
+                <$query_params as $crate::QueryDefinition>::get_query_definitions()
+                    .into_iter()
+                    .map(|p| {
+                        $crate::swagger_object::ParameterObjectOrReferenceObject::ParameterObject(
+                            Box::new(p),
+                        )
+                    })
+                    .collect(),
+            ),
             None,
-            None,
-            vec![(
-                200 as u16,
-                // ($description, $response::get_json_schema_definition()),
-                ($description, &json!(&schema_for!($response).schema)["title"].to_string().trim_matches('\"').to_string(), json!(&schema_for!($response).schema)),
-            )],
+            $crate::swagger_add_router!(@responses $($responses)*),
         )
     }};
-    ($swagger_object:expr,$secure:ident, $tag:literal, $method:literal, $path:literal, "request_body", $req: ident, 200, $description: expr, $response:ident) => {{
-        use struct2swagger::swagger_object::{
-            MediaTypeObject, RequestBodyObject, SchemaObjectOrReferenceObject,
-        };
-        let mut content_hash_map = HashMap::new();
-        content_hash_map.insert(
-            "application/json".to_owned(),
-            MediaTypeObject {
-                // TODO
-                // use SchemaObjectOrReferenceObject::ReferenceObject for ref to Schemars, not SchemaObject?
-                schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                    json!(&schema_for!($req).schema),
-                ))),
-                // Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                //     $req::get_json_schema_definition(),
-                // ))),
-                example: None,
-                examples: None,
-                encoding: None,
-            },
-        );
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, $query_params:ident, $status:tt, $description:expr, $response:ty) => {
+        $crate::swagger_add_router!($swagger_object, $secure, $tag, $method, $path, $query_params, [$status => ($description, $response)])
+    };
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, [$($responses:tt)*]) => {{
         $swagger_object.add_route(
             $secure,
             $tag,
-            $method,
+            $crate::swagger_add_router!(@method $method),
             String::from($path),
+            // Check path if we need a ParameterObject
             None,
-            Some(
-                (&json!(&schema_for!($req).schema)["title"].to_string().trim_matches('\"').to_string(),
-                RequestBodyObject {
-                    description: None,
-                    content: content_hash_map,
-                    required: Some(true),
-                })
-            ),
-            vec![(
-                200 as u16,
-                // ($description, $response::get_json_schema_definition()),
-                ($description, &json!(&schema_for!($response).schema)["title"].to_string().trim_matches('\"').to_string(), json!(&schema_for!($response).schema)),
-
-            )],
+            None,
+            $crate::swagger_add_router!(@responses $($responses)*),
         )
     }};
+    ($swagger_object:expr, $secure:ident, $tag:literal, $method:tt, $path:literal, $status:tt, $description:expr, $response:ty) => {
+        $crate::swagger_add_router!($swagger_object, $secure, $tag, $method, $path, [$status => ($description, $response)])
+    };
 }
@@ -0,0 +1,225 @@
+use serde_json::{Map, Value};
+
+use crate::swagger_object::DiscriminatorObject;
+
+/// Keywords whose values are instances rather than schemas, left untouched.
+const INSTANCE_KEYWORDS: &[&str] = &["const", "default", "enum", "example", "examples"];
+
+/// Keywords whose values map names to schemas, the names being free to look like keywords.
+const NAMED_SCHEMAS_KEYWORDS: &[&str] = &[
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependencies",
+];
+
+/// Calls `f` on every subschema of the schema `object`.
+fn for_each_subschema(object: &mut Map<String, Value>, f: &mut impl FnMut(&mut Value)) {
+    for (key, value) in object.iter_mut() {
+        if INSTANCE_KEYWORDS.contains(&key.as_str()) {
+            continue;
+        }
+        match value {
+            Value::Object(schemas) if NAMED_SCHEMAS_KEYWORDS.contains(&key.as_str()) => {
+                schemas.values_mut().for_each(&mut *f)
+            }
+            Value::Array(schemas) => schemas.iter_mut().for_each(&mut *f),
+            value => f(value),
+        }
+    }
+}
+
+/// Turns a draft-07 JSON Schema, like the ones of `schemars::schema_for!`, into an OpenAPI 3.0 one.
+///
+/// References to `#/definitions/` point to `#/components/schemas/`, and the `null` type
+/// becomes `nullable`, which is the only way OpenAPI 3.0 has to allow it.
+/// Tuple `items`, one schema per position, become `oneOf` the element schemas.
+/// Boolean schemas, `const` and `examples`, unknown to OpenAPI 3.0, are replaced too.
+pub fn to_openapi3(schema: &mut Value) {
+    if let Value::Object(object) = schema {
+        for_each_subschema(object, &mut to_openapi3);
+        convert_keywords(object);
+    }
+}
+
+fn convert_keywords(schema: &mut Map<String, Value>) {
+    if let Some(Value::String(reference)) = schema.get_mut("$ref") {
+        if let Some(name) = reference.strip_prefix("#/definitions/") {
+            *reference = format!("#/components/schemas/{}", name);
+        }
+    }
+
+    for keyword in &["items", "additionalItems", "not"] {
+        if let Some(subschema) = schema.get_mut(*keyword) {
+            replace_boolean_schema(subschema);
+        }
+    }
+    for keyword in &["properties", "patternProperties", "allOf", "anyOf", "oneOf"] {
+        match schema.get_mut(*keyword) {
+            Some(Value::Object(subschemas)) => {
+                subschemas.values_mut().for_each(replace_boolean_schema)
+            }
+            Some(Value::Array(subschemas)) => {
+                subschemas.iter_mut().for_each(replace_boolean_schema)
+            }
+            _ => {}
+        }
+    }
+    if let Some(value) = schema.remove("const") {
+        schema.insert("enum".to_owned(), json!([value]));
+    }
+    if let Some(Value::Array(examples)) = schema.remove("examples") {
+        if let Some(example) = examples.into_iter().next() {
+            schema.entry("example").or_insert(example);
+        }
+    }
+
+    if let Some(items) = schema.get_mut("items") {
+        if let Value::Array(tuple_items) = items {
+            *items = crate::tuple_items_schema(std::mem::take(tuple_items));
+        }
+    }
+
+    let mut nullable = false;
+
+    if let Some(Value::Array(types)) = schema.get_mut("type") {
+        let len = types.len();
+        types.retain(|ty| ty != "null");
+        nullable |= types.len() != len;
+        if types.len() == 1 {
+            let ty = types.remove(0);
+            schema.insert("type".to_owned(), ty);
+        }
+    }
+
+    for keyword in &["anyOf", "oneOf"] {
+        if let Some(Value::Array(subschemas)) = schema.get_mut(*keyword) {
+            let len = subschemas.len();
+            subschemas.retain(|subschema| subschema != &json!({ "type": "null" }));
+            nullable |= subschemas.len() != len;
+        }
+    }
+    // `Option<T>`, written `allOf: [T]` by the OpenAPI 3.0 generator of schemars
+    if nullable {
+        if let Some(Value::Array(subschemas)) = schema.get("anyOf") {
+            if subschemas.len() == 1 {
+                let subschemas = schema.remove("anyOf").unwrap_or_default();
+                schema.insert("allOf".to_owned(), subschemas);
+            }
+        }
+    }
+
+    if nullable {
+        schema.insert("nullable".to_owned(), Value::Bool(true));
+    }
+}
+
+/// Replaces a boolean schema with the object schema accepting the same values.
+fn replace_boolean_schema(schema: &mut Value) {
+    match schema {
+        Value::Bool(true) => *schema = json!({}),
+        Value::Bool(false) => *schema = json!({ "not": {} }),
+        _ => {}
+    }
+}
+
+/// Moves the `definitions` nested anywhere in `schema` to `definitions`, with their name.
+pub fn take_definitions(schema: &mut Value, definitions: &mut Vec<(String, Value)>) {
+    if let Value::Object(object) = schema {
+        for keyword in &["definitions", "$defs"] {
+            if let Some(Value::Object(nested)) = object.remove(*keyword) {
+                for (name, mut definition) in nested {
+                    take_definitions(&mut definition, definitions);
+                    definitions.push((name, definition));
+                }
+            }
+        }
+        for_each_subschema(object, &mut |subschema| {
+            take_definitions(subschema, definitions)
+        });
+    }
+}
+
+/// Moves the alternatives of a tagged `oneOf` to their own components, named after the schema
+/// and their tag, which `oneOf` references and the discriminator maps.
+///
+/// The alternatives are tagged when they all require the same string property, limited to
+/// a different value each, which is how schemars and `#[derive(Swagger)]` document the enums
+/// with `#[serde(tag = "...")]`.
+pub fn take_tagged_variants(
+    name: &str,
+    schema: &mut Value,
+    definitions: &mut Vec<(String, Value)>,
+) {
+    let alternatives = match schema.get_mut("oneOf") {
+        Some(Value::Array(alternatives)) if !alternatives.is_empty() => alternatives,
+        _ => return,
+    };
+    let tag = match find_tag(alternatives) {
+        Some(tag) => tag,
+        None => return,
+    };
+
+    let mut mapping = std::collections::HashMap::new();
+    for alternative in alternatives.iter_mut() {
+        let value = get_tag_value(alternative, &tag)
+            .unwrap_or_default()
+            .to_owned();
+        let component = format!("{}_{}", name, get_component_name(&value));
+        let reference = format!("#/components/schemas/{}", component);
+        let variant = std::mem::replace(alternative, json!({ "$ref": reference }));
+        definitions.push((component, variant));
+        mapping.insert(value, reference);
+    }
+    schema["discriminator"] = json!(DiscriminatorObject {
+        property_name: tag,
+        mapping: Some(mapping),
+    });
+}
+
+/// Returns the property all the `alternatives` require, each with its own value.
+fn find_tag(alternatives: &[Value]) -> Option<String> {
+    let properties = alternatives[0].get("properties")?.as_object()?;
+    properties
+        .keys()
+        .find(|property| {
+            let mut values = vec![];
+            alternatives
+                .iter()
+                .all(|alternative| match get_tag_value(alternative, property) {
+                    Some(value) if !values.contains(&value) => {
+                        values.push(value);
+                        true
+                    }
+                    _ => false,
+                })
+        })
+        .cloned()
+}
+
+/// Returns the only value of the required `property` of `alternative`, if it is a string.
+fn get_tag_value<'a>(alternative: &'a Value, property: &str) -> Option<&'a str> {
+    let required = alternative.get("required")?.as_array()?;
+    if !required.iter().any(|required| required == property) {
+        return None;
+    }
+    match alternative["properties"][property]
+        .get("enum")?
+        .as_array()?
+        .as_slice()
+    {
+        [Value::String(value)] => Some(value),
+        _ => None,
+    }
+}
+
+/// Replaces the characters OpenAPI does not allow in component names.
+fn get_component_name(tag: &str) -> String {
+    tag.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde_json::Value;
+
+use crate::openapi_schema::take_definitions;
+use crate::swagger_object::{
+    merge_parameters, parse_path_template, HttpMethod, MediaTypeObject, OperationObject,
+    ParameterObjectOrReferenceObject, PathItemObject, RequestBodyObject,
+    RequestBodyObjectOrReferenceObject, ResponseObject, ResponseObjectOrReferenceObject,
+    ResponseStatus, ResponsesObject, SchemaError, SchemaObjectOrReferenceObject, SwaggerObject,
+};
+use crate::{ParameterObject, QueryDefinition};
+
+/// Documents an operation of a `SwaggerObject`, created with `SwaggerObject::operation`.
+///
+/// ```ignore
+/// swagger_object
+///     .operation(HttpMethod::Post, "/users/{id:u64}")
+///     .summary("Update a user")
+///     .tag("users")
+///     .body::<UpdateUser>()
+///     .response::<User>(200, "The updated user")
+///     .response::<ErrorBody>(ResponseStatus::Default, "Unexpected error")
+///     .finish()?;
+/// ```
+#[must_use = "the operation is only added to the document by `finish`"]
+pub struct OperationBuilder<'a> {
+    swagger_object: &'a mut SwaggerObject,
+    method: HttpMethod,
+    path: String,
+    pub(crate) parameters: Vec<ParameterObjectOrReferenceObject>,
+    operation: OperationObject,
+    /// The first path parameter or body schema which could not be documented, reported by `finish`.
+    error: Option<SchemaError>,
+}
+
+impl<'a> OperationBuilder<'a> {
+    pub(crate) fn new(
+        swagger_object: &'a mut SwaggerObject,
+        method: HttpMethod,
+        path: &str,
+    ) -> Self {
+        let (path, parameters, error) = match parse_path_template(path) {
+            Ok((path, parameters)) => (path, parameters, None),
+            Err(error) => (path.to_owned(), vec![], Some(error)),
+        };
+        OperationBuilder {
+            swagger_object,
+            method,
+            path,
+            parameters,
+            operation: OperationObject {
+                tags: None,
+                summary: None,
+                description: None,
+                external_docs: None,
+                operation_id: None,
+                parameters: None,
+                request_body: None,
+                responses: ResponsesObject {
+                    default: None,
+                    responses_per_http_status_codes: Some(HashMap::new()),
+                },
+                callbacks: None,
+                deprecated: None,
+                security: None,
+                servers: None,
+            },
+            error,
+        }
+    }
+
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.operation.summary = Some(summary.to_owned());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.operation.description = Some(description.to_owned());
+        self
+    }
+
+    pub fn operation_id(mut self, operation_id: &str) -> Self {
+        self.operation.operation_id = Some(operation_id.to_owned());
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.operation
+            .tags
+            .get_or_insert_with(Vec::new)
+            .push(tag.to_owned());
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.operation.deprecated = Some(true);
+        self
+    }
+
+    /// Requires the security scheme `name` with the given OAuth2 scopes, if any.
+    pub fn security(mut self, name: &str, scopes: &[&str]) -> Self {
+        let mut requirement = HashMap::new();
+        requirement.insert(
+            name.to_owned(),
+            scopes.iter().map(|scope| scope.to_string()).collect(),
+        );
+        self.operation
+            .security
+            .get_or_insert_with(Vec::new)
+            .push(requirement);
+        self
+    }
+
+    /// Documents the operation as public, overriding the `SwaggerObject` security requirements.
+    pub fn without_security(mut self) -> Self {
+        self.operation.security = Some(vec![]);
+        self
+    }
+
+    /// Adds parameters, replacing the ones with the same name and location.
+    pub fn parameters(mut self, parameters: Vec<ParameterObject>) -> Self {
+        let parameters = parameters
+            .into_iter()
+            .map(|parameter| ParameterObjectOrReferenceObject::ParameterObject(Box::new(parameter)))
+            .collect();
+        merge_parameters(&mut self.parameters, parameters);
+        self
+    }
+
+    /// Documents the route template parameters with the fields of `P`.
+    pub fn path<P: QueryDefinition>(self) -> Self {
+        self.parameters(P::get_path_definitions())
+    }
+
+    /// Documents the parameters described by `Q`, in the query string unless its fields say otherwise.
+    pub fn query<Q: QueryDefinition>(self) -> Self {
+        self.parameters(Q::get_query_definitions())
+    }
+
+    /// Documents a required JSON request body, registering its schema in `components.schemas`.
+    pub fn body<B: JsonSchema>(mut self) -> Self {
+        let (title, schema) = get_schema::<B>();
+        let content = self.json_content(&title, schema);
+        self.operation.request_body = Some(RequestBodyObjectOrReferenceObject::RequestBodyObject(
+            Box::new(RequestBodyObject {
+                description: None,
+                content,
+                required: Some(true),
+            }),
+        ));
+        self
+    }
+
+    /// Documents a JSON response, `status` is a status code or `ResponseStatus::Default`.
+    ///
+    /// The schema of `R` is registered in `components.schemas`.
+    pub fn response<R: JsonSchema>(
+        self,
+        status: impl Into<ResponseStatus>,
+        description: &str,
+    ) -> Self {
+        let body = get_schema::<R>();
+        self.add_response(status.into(), description, Some(body))
+    }
+
+    /// Documents a response without body, like `204 No Content`.
+    pub fn empty_response(self, status: impl Into<ResponseStatus>, description: &str) -> Self {
+        self.add_response(status.into(), description, None)
+    }
+
+    /// Adds the response of `status`, the body being the component title and the schema.
+    pub(crate) fn add_response(
+        mut self,
+        status: ResponseStatus,
+        description: &str,
+        body: Option<(String, Value)>,
+    ) -> Self {
+        let content = body.map(|(title, schema)| self.json_content(&title, schema));
+        let response = ResponseObjectOrReferenceObject::ResponseObject(Box::new(ResponseObject {
+            description: description.to_owned(),
+            headers: None,
+            content,
+            links: None,
+        }));
+        let responses = &mut self.operation.responses;
+        match status {
+            ResponseStatus::Code(status_code) => {
+                responses
+                    .responses_per_http_status_codes
+                    .get_or_insert_with(HashMap::new)
+                    .insert(status_code, response);
+            }
+            ResponseStatus::Default => responses.default = Some(response),
+        }
+        self
+    }
+
+    /// Sets the request body, its `application/json` schema referencing the `title` component.
+    pub(crate) fn request_body(mut self, title: &str, mut request_body: RequestBodyObject) -> Self {
+        if let Some(media_type) = request_body.content.remove("application/json") {
+            let schema = match media_type.schema {
+                Some(SchemaObjectOrReferenceObject::SchemaObject(schema)) => *schema,
+                _ => json!({}),
+            };
+            let content = self.json_content(title, schema);
+            request_body.content.extend(content);
+        }
+        self.operation.request_body = Some(RequestBodyObjectOrReferenceObject::RequestBodyObject(
+            Box::new(request_body),
+        ));
+        self
+    }
+
+    /// Returns the `application/json` content, referencing the `title` component.
+    ///
+    /// `schema` is a root JSON Schema, registered with its definitions on first use.
+    /// Strings and untitled schemas have no component, their schema is inlined.
+    fn json_content(&mut self, title: &str, schema: Value) -> HashMap<String, MediaTypeObject> {
+        let schema = if title.is_empty() || title == "String" {
+            let mut schema = schema;
+            if let Some(schema) = schema.as_object_mut() {
+                schema.remove("$schema");
+            }
+            schema
+        } else {
+            if let Err(error) = self.swagger_object.add_schema_value(schema) {
+                self.error.get_or_insert(error);
+            }
+            json!({ "$ref": format!("#/components/schemas/{}", title) })
+        };
+
+        let mut content = HashMap::new();
+        content.insert(
+            "application/json".to_owned(),
+            MediaTypeObject {
+                schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
+                    schema,
+                ))),
+                example: None,
+                examples: None,
+                encoding: None,
+            },
+        );
+        content
+    }
+
+    /// Adds the operation to its `PathItemObject`, replacing the one with the same method.
+    ///
+    /// The components referenced by the parameters are registered in `components.schemas`.
+    ///
+    /// Fails, without adding the operation, when a body or parameter schema has the name of
+    /// a different schema already in `components.schemas`.
+    pub fn finish(mut self) -> Result<(), SchemaError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let mut definitions = vec![];
+        for parameter in self.parameters.iter_mut() {
+            if let ParameterObjectOrReferenceObject::ParameterObject(parameter) = parameter {
+                if let Some(SchemaObjectOrReferenceObject::SchemaObject(schema)) =
+                    &mut parameter.schema
+                {
+                    take_definitions(schema, &mut definitions);
+                }
+            }
+        }
+        self.swagger_object.add_schemas(definitions)?;
+        if !self.parameters.is_empty() {
+            self.operation.parameters = Some(self.parameters);
+        }
+
+        let path_object = self
+            .swagger_object
+            .paths
+            .entry(self.path)
+            .or_insert_with(|| PathItemObject {
+                r#ref: None,
+                summary: None,
+                description: None,
+                get: None,
+                put: None,
+                post: None,
+                delete: None,
+                options: None,
+                head: None,
+                patch: None,
+                trace: None,
+                servers: None,
+                parameters: None,
+            });
+        let operation = match self.method {
+            HttpMethod::Get => &mut path_object.get,
+            HttpMethod::Put => &mut path_object.put,
+            HttpMethod::Post => &mut path_object.post,
+            HttpMethod::Delete => &mut path_object.delete,
+            HttpMethod::Options => &mut path_object.options,
+            HttpMethod::Head => &mut path_object.head,
+            HttpMethod::Patch => &mut path_object.patch,
+            HttpMethod::Trace => &mut path_object.trace,
+        };
+        *operation = Some(self.operation);
+        Ok(())
+    }
+}
+
+/// Returns the component title and the root schema of `T`.
+fn get_schema<T: JsonSchema>() -> (String, Value) {
+    let schema = crate::get_openapi3_schema::<T>();
+    let title = schema["title"].as_str().unwrap_or_default().to_owned();
+    (title, schema)
+}
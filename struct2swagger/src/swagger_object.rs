@@ -1,10 +1,16 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
 
 use serde::{Serialize, Serializer};
 
+use serde_json::value::Value;
+
 use schemars::JsonSchema;
 
-use serde_json::value::Value;
+use crate::openapi_schema;
+pub use crate::operation_builder::OperationBuilder;
+use crate::JsonSchemaDefinition;
 
 #[derive(Debug, Clone)]
 pub enum SwaggerVersion {
@@ -23,6 +29,98 @@ impl Serialize for SwaggerVersion {
 
 type HttpStatusCode = u16;
 
+/// The methods an operation can be documented for in a `PathItemObject`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpMethod {
+    Get,
+    Put,
+    Post,
+    Delete,
+    Options,
+    Head,
+    Patch,
+    Trace,
+}
+
+impl HttpMethod {
+    pub const ALL: [HttpMethod; 8] = [
+        HttpMethod::Get,
+        HttpMethod::Put,
+        HttpMethod::Post,
+        HttpMethod::Delete,
+        HttpMethod::Options,
+        HttpMethod::Head,
+        HttpMethod::Patch,
+        HttpMethod::Trace,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Put => "PUT",
+            HttpMethod::Post => "POST",
+            HttpMethod::Delete => "DELETE",
+            HttpMethod::Options => "OPTIONS",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Patch => "PATCH",
+            HttpMethod::Trace => "TRACE",
+        }
+    }
+
+    /// Finds the method named `name`, ignoring the case.
+    pub const fn from_name(name: &str) -> Option<HttpMethod> {
+        let mut i = 0;
+        while i < HttpMethod::ALL.len() {
+            let method = HttpMethod::ALL[i];
+            if eq_ignore_ascii_case(name.as_bytes(), method.as_str().as_bytes()) {
+                return Some(method);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+const fn eq_ignore_ascii_case(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if !a[i].eq_ignore_ascii_case(&b[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+impl fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The error returned when parsing an unknown `HttpMethod`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownHttpMethod(pub String);
+
+impl fmt::Display for UnknownHttpMethod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown HTTP method `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownHttpMethod {}
+
+impl FromStr for HttpMethod {
+    type Err = UnknownHttpMethod;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        HttpMethod::from_name(name).ok_or_else(|| UnknownHttpMethod(name.to_owned()))
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ContactObject {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -158,6 +256,25 @@ pub struct PathItemObject {
     pub parameters: Option<Vec<ParameterObjectOrReferenceObject>>,
 }
 
+impl PathItemObject {
+    /// The operations documented for this path, with their method.
+    pub fn operations(&self) -> impl Iterator<Item = (HttpMethod, &OperationObject)> {
+        let operations = [
+            (HttpMethod::Get, &self.get),
+            (HttpMethod::Put, &self.put),
+            (HttpMethod::Post, &self.post),
+            (HttpMethod::Delete, &self.delete),
+            (HttpMethod::Options, &self.options),
+            (HttpMethod::Head, &self.head),
+            (HttpMethod::Patch, &self.patch),
+            (HttpMethod::Trace, &self.trace),
+        ];
+        IntoIterator::into_iter(operations).filter_map(|(method, operation)| {
+            operation.as_ref().map(|operation| (method, operation))
+        })
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OperationObject {
@@ -172,11 +289,10 @@ pub struct OperationObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub operation_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    // pub parameters: Option<Vec<ParameterObjectOrReferenceObject>>,
-    pub parameters: Option<Vec<Value>>,
+    pub parameters: Option<Vec<ParameterObjectOrReferenceObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_body: Option<RequestBodyObjectOrReferenceObject>,
-    responses: ResponsesObject,
+    pub responses: ResponsesObject,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub callbacks: Option<HashMap<String, CallbackObjectOrReferenceObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -194,7 +310,7 @@ pub struct ExternalDocumentationObject {
     pub url: String,
 }
 
-#[derive(Clone, Serialize, Debug)]
+#[derive(Clone, Serialize, Debug, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ParameterIn {
     Query,
@@ -203,6 +319,35 @@ pub enum ParameterIn {
     Cookie,
 }
 
+impl ParameterIn {
+    /// The serialization style OpenAPI assumes for the parameters in this location.
+    pub fn default_style(&self) -> ParameterStyle {
+        match self {
+            ParameterIn::Query | ParameterIn::Cookie => ParameterStyle::Form,
+            ParameterIn::Header | ParameterIn::Path => ParameterStyle::Simple,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParameterStyle {
+    Matrix,
+    Label,
+    Form,
+    Simple,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
+}
+
+impl ParameterStyle {
+    /// Whether arrays and objects are exploded when `explode` is not set: only the `form` style does.
+    pub fn default_explode(&self) -> bool {
+        *self == ParameterStyle::Form
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ParameterObject {
@@ -218,6 +363,12 @@ pub struct ParameterObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_empty_value: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ParameterStyle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub schema: Option<SchemaObjectOrReferenceObject>,
 }
 
@@ -271,24 +422,40 @@ impl Serialize for ResponsesObject {
         S: Serializer,
     {
         let mut r: HashMap<String, ResponseObjectOrReferenceObject> = HashMap::new();
-        if self.responses_per_http_status_codes.is_some() {
-            for (k, value) in self
-                .responses_per_http_status_codes
-                .as_ref()
-                .unwrap()
-                .iter()
-            {
-                r.insert(k.to_string().to_owned(), value.clone());
+        if let Some(responses_per_http_status_codes) = &self.responses_per_http_status_codes {
+            for (k, value) in responses_per_http_status_codes.iter() {
+                r.insert(k.to_string(), value.clone());
             }
         }
-        if self.default.is_some() {
-            r.insert("default".to_owned(), self.default.as_ref().unwrap().clone());
+        if let Some(default) = &self.default {
+            r.insert("default".to_owned(), default.clone());
         }
 
         r.serialize(serializer)
     }
 }
 
+/// The status a response is documented for, see `SwaggerObject::add_route`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseStatus {
+    Code(HttpStatusCode),
+    /// The response of the status codes not documented otherwise.
+    Default,
+}
+
+/// A response of `SwaggerObject::add_route`: its status, description and, when it has a body,
+/// the component title and the root JSON Schema of the body.
+pub type RouteResponse<'a> = (
+    ResponseStatus,
+    (&'a str, Option<(String, serde_json::Value)>),
+);
+
+impl From<HttpStatusCode> for ResponseStatus {
+    fn from(status_code: HttpStatusCode) -> Self {
+        ResponseStatus::Code(status_code)
+    }
+}
+
 #[derive(Serialize, Debug, Clone)]
 pub struct ResponseObject {
     pub description: String,
@@ -394,7 +561,8 @@ pub struct SecuritySchemeObject {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub r#in: Option<ParameterIn>,
-    pub scheme: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bearer_format: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -403,30 +571,219 @@ pub struct SecuritySchemeObject {
     pub open_id_connect_url: Option<String>,
 }
 
-#[derive(Serialize, Debug, Clone)]
+impl SecuritySchemeObject {
+    fn new(r#type: &str) -> Self {
+        SecuritySchemeObject {
+            r#type: r#type.to_owned(),
+            description: None,
+            name: None,
+            r#in: None,
+            scheme: None,
+            bearer_format: None,
+            flows: None,
+            open_id_connect_url: None,
+        }
+    }
+
+    /// An API key sent in the header, the query parameter or the cookie `name`.
+    pub fn api_key(name: &str, location: ParameterIn) -> Self {
+        SecuritySchemeObject {
+            name: Some(name.to_owned()),
+            r#in: Some(location),
+            ..SecuritySchemeObject::new("apiKey")
+        }
+    }
+
+    /// An HTTP authentication scheme, like `basic`, see RFC 7235.
+    pub fn http(scheme: &str) -> Self {
+        SecuritySchemeObject {
+            scheme: Some(scheme.to_owned()),
+            ..SecuritySchemeObject::new("http")
+        }
+    }
+
+    /// An HTTP bearer token, `bearer_format` hints how it is built, like `JWT`.
+    pub fn bearer(bearer_format: Option<&str>) -> Self {
+        SecuritySchemeObject {
+            bearer_format: bearer_format.map(str::to_owned),
+            ..SecuritySchemeObject::http("bearer")
+        }
+    }
+
+    pub fn oauth2(flows: OAuthFlowsObject) -> Self {
+        SecuritySchemeObject {
+            flows: Some(flows),
+            ..SecuritySchemeObject::new("oauth2")
+        }
+    }
+
+    pub fn open_id_connect(open_id_connect_url: &str) -> Self {
+        SecuritySchemeObject {
+            open_id_connect_url: Some(open_id_connect_url.to_owned()),
+            ..SecuritySchemeObject::new("openIdConnect")
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+}
+
+/// The OAuth2 flows of a security scheme, each with the fields the spec requires for it.
+#[derive(Serialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct OAuthFlowsObject {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub implicit: Option<OAuthFlowObject>,
+    pub implicit: Option<ImplicitOAuthFlowObject>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub password: Option<OAuthFlowObject>,
+    pub password: Option<PasswordOAuthFlowObject>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub client_credentials: Option<OAuthFlowObject>,
+    pub client_credentials: Option<ClientCredentialsOAuthFlowObject>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub authorization_code: Option<OAuthFlowObject>,
+    pub authorization_code: Option<AuthorizationCodeOAuthFlowObject>,
 }
 
+impl OAuthFlowsObject {
+    /// Whether one of the flows declares `scope`.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        let scopes = [
+            self.implicit.as_ref().map(|flow| &flow.scopes),
+            self.password.as_ref().map(|flow| &flow.scopes),
+            self.client_credentials.as_ref().map(|flow| &flow.scopes),
+            self.authorization_code.as_ref().map(|flow| &flow.scopes),
+        ];
+        scopes
+            .iter()
+            .flatten()
+            .any(|scopes| scopes.contains_key(scope))
+    }
+}
+
+/// The implicit flow has no token URL, the token is returned by the authorization endpoint.
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct OAuthFlowObject {
+pub struct ImplicitOAuthFlowObject {
     pub authorization_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_url: Option<String>,
+    pub scopes: HashMap<String, String>,
+}
+
+/// The resource owner password flow has no authorization URL.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordOAuthFlowObject {
     pub token_url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refresh_url: Option<String>,
     pub scopes: HashMap<String, String>,
 }
 
-type SecurityRequirementObject = HashMap<String, Value>;
+/// The client credentials flow has no authorization URL.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCredentialsOAuthFlowObject {
+    pub token_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_url: Option<String>,
+    pub scopes: HashMap<String, String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationCodeOAuthFlowObject {
+    pub authorization_url: String,
+    pub token_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_url: Option<String>,
+    pub scopes: HashMap<String, String>,
+}
+
+/// The error returned when registering a schema in `components.schemas`,
+/// or when typing a path parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    /// The schema has no title to name it after.
+    MissingTitle,
+    /// A different schema is already registered with this name.
+    Conflict(String),
+    /// The annotation of a route template parameter, like `{id:u46}`, is neither a known type
+    /// nor a regular expression.
+    UnknownPathType {
+        parameter: String,
+        annotation: String,
+    },
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaError::MissingTitle => f.write_str("the schema has no title"),
+            SchemaError::Conflict(name) => {
+                write!(f, "two different schemas are named `{}`", name)
+            }
+            SchemaError::UnknownPathType {
+                parameter,
+                annotation,
+            } => write!(
+                f,
+                "unknown type `{}` of the path parameter `{}`",
+                annotation, parameter
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// The error returned by `SwaggerObject::validate_security`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecurityError {
+    /// A requirement references a scheme missing from `components.securitySchemes`.
+    UnknownScheme { operation: String, scheme: String },
+    /// A requirement asks for a scope no OAuth2 flow of the scheme declares.
+    UnknownScope {
+        operation: String,
+        scheme: String,
+        scope: String,
+    },
+    /// A requirement asks for scopes of a scheme which is neither OAuth2 nor OpenID Connect.
+    UnexpectedScopes { operation: String, scheme: String },
+}
+
+impl fmt::Display for SecurityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecurityError::UnknownScheme { operation, scheme } => {
+                write!(
+                    f,
+                    "{} requires the unknown security scheme `{}`",
+                    operation, scheme
+                )
+            }
+            SecurityError::UnknownScope {
+                operation,
+                scheme,
+                scope,
+            } => write!(
+                f,
+                "{} requires the scope `{}` not declared by the security scheme `{}`",
+                operation, scope, scheme
+            ),
+            SecurityError::UnexpectedScopes { operation, scheme } => write!(
+                f,
+                "{} requires scopes of the security scheme `{}`, which has none",
+                operation, scheme
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SecurityError {}
+
+/// The schemes required at once, with their scopes.
+type SecurityRequirementObject = HashMap<String, Vec<String>>;
 
 #[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -439,7 +796,7 @@ pub struct SwaggerObject {
     // #[serde(skip_serializing_if = "Option::is_none")]
     pub components: ComponentsObject,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub security: Option<SecurityRequirementObject>,
+    pub security: Option<Vec<SecurityRequirementObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<TagObject>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -447,6 +804,12 @@ pub struct SwaggerObject {
 }
 
 impl SwaggerObject {
+    /// Creates a document registering the given JSON Schemas, like the ones of `schemars::schema_for!`.
+    ///
+    /// # Panics
+    ///
+    /// When a schema has no title, or when two different schemas have the same name,
+    /// see `try_new` to handle these errors.
     pub fn new(
         title: &str,
         version: &str,
@@ -454,47 +817,21 @@ impl SwaggerObject {
         description: &str,
         schemas: Option<Vec<Value>>,
     ) -> Self {
-        let mut content_map = HashMap::new();
-        if schemas != None {
-            for schema in schemas.unwrap() {
-                // This is a ugly $ref replace, better use Schemars SchemaGenerator?.
-                let mut gen_schema =
-                    format!("{}", schema).replace(r#"#/definitions/"#, r#"#/components/schemas/"#);
-                // v3.1.0 allow null, we use v3.0.0 and remove null and replace with nullable: true
-                gen_schema = gen_schema.replace(r#","null"]"#, r#"],"nullable":true"#);
-                gen_schema = gen_schema.replace(r#","null"]"#, r#"],"nullable":true"#);
-                gen_schema = gen_schema.replace(r#",{"type":"null"}]"#, r#"],"nullable":true"#);
-                // normalisation
-                gen_schema = gen_schema.replace(r#""type":["array"]"#, r#""type":"array""#);
-                gen_schema = gen_schema.replace(r#""type":["object"]"#, r#""type":"object""#);
-                gen_schema = gen_schema.replace(r#""type":["string"]"#, r#""type":"string""#);
-                gen_schema = gen_schema.replace(r#""type":["boolean"]"#, r#""type":"boolean""#);
-                gen_schema = gen_schema.replace(r#""type":["integer"]"#, r#""type":"integer""#);
-                gen_schema = gen_schema.replace(r#""type":["number"]"#, r#""type":"number""#);
-                // ip fix ;(
-                gen_schema = gen_schema.replace(
-                    r##"{"$ref":"#/components/schemas/Ipv6Net"}"##,
-                    r#"{"type":"string"}"#,
-                );
-                gen_schema = gen_schema.replace(
-                    r##"{"$ref":"#/components/schemas/IpNet"}"##,
-                    r#"{"type":"string"}"#,
-                );
-
-                let new_schema = serde_json::from_str(&gen_schema).unwrap();
-
-                content_map.insert(
-                    schema
-                        .get("title")
-                        .unwrap()
-                        .to_string()
-                        .trim_matches('"')
-                        .to_string(), // need the name
-                    new_schema,
-                );
-            }
+        match Self::try_new(title, version, servers, description, schemas) {
+            Ok(swagger_object) => swagger_object,
+            Err(error) => panic!("{}", error),
         }
+    }
 
+    /// Creates a document registering the given JSON Schemas, like `new`,
+    /// failing when `add_schema_value` fails for one of them.
+    pub fn try_new(
+        title: &str,
+        version: &str,
+        servers: Vec<String>,
+        description: &str,
+        schemas: Option<Vec<Value>>,
+    ) -> Result<Self, SchemaError> {
         // Servers
         let mut new_servers = vec![];
         for server in servers {
@@ -505,24 +842,7 @@ impl SwaggerObject {
             })
         }
 
-        // set Auth
-        let mut auth = HashMap::new();
-        auth.insert(
-            "bearerAuth".to_string(),
-            SecuritySchemeObjectOrReferenceObject::SecuritySchemeObject(Box::new(
-                SecuritySchemeObject {
-                    r#type: "http".to_string(),
-                    description: Some("Bearer Authentication See RFC 6750".to_string()),
-                    name: None,
-                    r#in: None,
-                    scheme: "bearer".to_string(),
-                    bearer_format: Some("JWT".to_string()),
-                    flows: None,
-                    open_id_connect_url: None,
-                },
-            )),
-        );
-        Self {
+        let mut swagger_object = Self {
             openapi: SwaggerVersion::V300,
             info: InfoObject {
                 title: title.to_owned(),
@@ -536,7 +856,7 @@ impl SwaggerObject {
             paths: HashMap::new(),
             // use the components add the schemas
             components: ComponentsObject {
-                schemas: Some(content_map),
+                schemas: Some(HashMap::new()),
                 callbacks: None,
                 examples: None,
                 headers: None,
@@ -544,194 +864,374 @@ impl SwaggerObject {
                 request_bodies: None,
                 responses: None,
                 parameters: None,
-                security_schemes: Some(auth),
+                security_schemes: None,
             },
 
             security: None,
             tags: None,
             external_docs: None,
+        };
+
+        for schema in schemas.unwrap_or_default() {
+            swagger_object.add_schema_value(schema)?;
+        }
+
+        Ok(swagger_object)
+    }
+
+    /// Registers the schema of `T` and the ones it references in `components.schemas`.
+    ///
+    /// The schemas are named after `JsonSchema::schema_name` and generated for OpenAPI 3.0
+    /// like the ones of the routes, so registering `T` again from a route is a no-op.
+    pub fn add_schema<T: JsonSchema>(&mut self) -> Result<(), SchemaError> {
+        self.add_schema_value(crate::get_openapi3_schema::<T>())
+    }
+
+    /// Registers a JSON Schema named after its title, generated by schemars for OpenAPI 3.0
+    /// or for draft-07 like `json!(schemars::schema_for!(T))`, which is converted.
+    ///
+    /// The nested `definitions` are registered as well, and the references to them rewritten.
+    /// The variants of the tagged enums are registered as `<Enum>_<tag>`, for their discriminator
+    /// to map the tags to them.
+    pub fn add_schema_value(&mut self, mut schema: Value) -> Result<(), SchemaError> {
+        let mut definitions = vec![];
+        openapi_schema::take_definitions(&mut schema, &mut definitions);
+        if let Some(schema) = schema.as_object_mut() {
+            schema.remove("$schema");
+        }
+        let name = match schema.get("title") {
+            Some(Value::String(title)) => title.clone(),
+            _ => return Err(SchemaError::MissingTitle),
+        };
+        definitions.push((name, schema));
+        self.add_schemas(definitions)
+    }
+
+    /// Registers the schema `#[derive(Swagger)]` generates for `T` and the ones it references
+    /// in `components.schemas`, named after `JsonSchemaDefinition::get_component_name`.
+    ///
+    /// The types with `#[swagger(component)]` are registered along with the schemas
+    /// referencing them, a route using such a type needs no explicit registration.
+    pub fn add_component<T: JsonSchemaDefinition + ?Sized>(&mut self) -> Result<(), SchemaError> {
+        let mut schema = T::get_json_schema_definition();
+        let mut definitions = vec![];
+        openapi_schema::take_definitions(&mut schema, &mut definitions);
+        if schema.get("$ref").is_none() {
+            definitions.push((T::get_component_name(), schema));
+        }
+        self.add_schemas(definitions)
+    }
+
+    /// Registers the named schemas and the variants of their tagged enums, converted for OpenAPI 3.0.
+    ///
+    /// A schema already registered with the same name is kept. Nothing is registered when
+    /// a different schema has the name of another one, the `title` being ignored as root
+    /// schemas have one while their copies in `definitions` don't.
+    pub(crate) fn add_schemas(
+        &mut self,
+        mut schemas: Vec<(String, Value)>,
+    ) -> Result<(), SchemaError> {
+        fn untitled(schema: &Value) -> Value {
+            let mut schema = schema.clone();
+            if let Some(schema) = schema.as_object_mut() {
+                schema.remove("title");
+            }
+            schema
+        }
+
+        let mut variants = vec![];
+        for (name, schema) in schemas.iter_mut() {
+            openapi_schema::take_tagged_variants(name, schema, &mut variants);
+        }
+
+        let registered = self.components.schemas.get_or_insert_with(HashMap::new);
+        let mut new_schemas: HashMap<String, Value> = HashMap::new();
+        for (name, mut schema) in schemas.into_iter().chain(variants) {
+            openapi_schema::to_openapi3(&mut schema);
+            match registered.get(&name).or_else(|| new_schemas.get(&name)) {
+                Some(same) if untitled(same) != untitled(&schema) => {
+                    return Err(SchemaError::Conflict(name))
+                }
+                Some(_) => {}
+                None => {
+                    new_schemas.insert(name, schema);
+                }
+            }
+        }
+        registered.extend(new_schemas);
+        Ok(())
+    }
+
+    /// Registers the security scheme `name`, for the operations to require it.
+    pub fn add_security_scheme(&mut self, name: &str, security_scheme: SecuritySchemeObject) {
+        self.components
+            .security_schemes
+            .get_or_insert_with(HashMap::new)
+            .insert(
+                name.to_owned(),
+                SecuritySchemeObjectOrReferenceObject::SecuritySchemeObject(Box::new(
+                    security_scheme,
+                )),
+            );
+    }
+
+    /// Requires the security scheme `name` by default, with the given OAuth2 scopes if any.
+    ///
+    /// Every requirement added is an alternative, operations can override them.
+    pub fn add_security(&mut self, name: &str, scopes: &[&str]) {
+        let mut requirement = HashMap::new();
+        requirement.insert(
+            name.to_owned(),
+            scopes.iter().map(|scope| scope.to_string()).collect(),
+        );
+        self.security.get_or_insert_with(Vec::new).push(requirement);
+    }
+
+    /// Checks that the security requirements reference registered schemes,
+    /// and that OAuth2 ones only ask for the scopes declared by the scheme flows.
+    pub fn validate_security(&self) -> Result<(), SecurityError> {
+        let mut requirements = vec![];
+        if let Some(security) = &self.security {
+            requirements.push(("the global security".to_owned(), security));
+        }
+        for (path, path_item) in &self.paths {
+            for (method, operation) in path_item.operations() {
+                if let Some(security) = &operation.security {
+                    requirements.push((format!("{} {}", method, path), security));
+                }
+            }
+        }
+
+        let schemes = self.components.security_schemes.as_ref();
+        for (operation, security) in requirements {
+            for (scheme, scopes) in security.iter().flatten() {
+                let security_scheme = match schemes.and_then(|schemes| schemes.get(scheme)) {
+                    Some(SecuritySchemeObjectOrReferenceObject::SecuritySchemeObject(
+                        security_scheme,
+                    )) => security_scheme,
+                    // the referenced scheme lives outside of this document
+                    Some(SecuritySchemeObjectOrReferenceObject::ReferenceObject(_)) => continue,
+                    None => {
+                        return Err(SecurityError::UnknownScheme {
+                            operation,
+                            scheme: scheme.clone(),
+                        })
+                    }
+                };
+                match (&security_scheme.flows, security_scheme.r#type.as_str()) {
+                    (Some(flows), _) => {
+                        if let Some(scope) = scopes.iter().find(|scope| !flows.has_scope(scope)) {
+                            return Err(SecurityError::UnknownScope {
+                                operation,
+                                scheme: scheme.clone(),
+                                scope: scope.clone(),
+                            });
+                        }
+                    }
+                    (None, "openIdConnect") => {}
+                    (None, _) if !scopes.is_empty() => {
+                        return Err(SecurityError::UnexpectedScopes {
+                            operation,
+                            scheme: scheme.clone(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
         }
+
+        Ok(())
+    }
+
+    /// Starts documenting the `method` operation of `path`, a route template like `/users/{id:u64}`.
+    pub fn operation(&mut self, method: HttpMethod, path: &str) -> OperationBuilder<'_> {
+        OperationBuilder::new(self, method, path)
     }
 
+    /// Documents a route, `secure` ones require the `bearerAuth` scheme.
+    ///
+    /// `bearerAuth` is registered as a JWT bearer token unless it is already.
+    /// The request and response body schemas, root JSON Schemas like `json!(schema_for!(T))`,
+    /// are registered in `components.schemas` with their definitions.
+    ///
+    /// Fails, without documenting the route, when a different schema is already registered
+    /// with the name of a body schema.
+    #[allow(clippy::too_many_arguments)]
     pub fn add_route(
-        self: &mut Self,
+        &mut self,
         secure: bool,
         tag: &str,
-        method: &str,
+        method: HttpMethod,
         path: String,
         parameters: Option<Vec<ParameterObjectOrReferenceObject>>,
         request_body: Option<(&str, RequestBodyObject)>,
-        responses: Vec<(HttpStatusCode, (&str, &str, serde_json::Value))>,
-    ) {
-        if !self.paths.contains_key(&path) {
-            self.paths.insert(
-                path.clone(),
-                PathItemObject {
-                    r#ref: None,
-                    summary: None,
-                    description: None,
-                    get: None,
-                    put: None,
-                    post: None,
-                    delete: None,
-                    options: None,
-                    head: None,
-                    patch: None,
-                    trace: None,
-                    servers: None,
-                    parameters: None,
-                },
-            );
+        responses: Vec<RouteResponse>,
+    ) -> Result<(), SchemaError> {
+        if secure {
+            let has_bearer_auth = self
+                .components
+                .security_schemes
+                .as_ref()
+                .is_some_and(|schemes| schemes.contains_key("bearerAuth"));
+            if !has_bearer_auth {
+                self.add_security_scheme(
+                    "bearerAuth",
+                    SecuritySchemeObject::bearer(Some("JWT"))
+                        .description("Bearer Authentication See RFC 6750"),
+                );
+            }
         }
 
-        // if path contains parameters {} or :id return:
-        // parameters:
-        // - name: id
-        //   in: path
-        //   description: User ID
-        //   required: true
-        //   schema:
-        //     type: integer
-        //     format: int64
-
-        // generate the paramter object from url {}
-        let mut new_parameter_objects: Option<Vec<Value>> = None;
-        if path.contains("{") {
-            let patterns: &[_] = &[':', '{', '}'];
-            let split_paths: Vec<&str> = path.split('/').collect();
-            for data_path in split_paths {
-                if data_path.contains("{") {
-                    let new_path_name = data_path.trim_matches(patterns);
-                    let parmeter = json!(ParameterObject {
-                        name: new_path_name.to_string(),
-                        description: Some(format!("use {} parameter", &new_path_name)),
-                        required: Some(true),
-                        schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                            json!({
-                                "type": "string"
-                            }),
-                        ))),
-                        allow_empty_value: None,
-                        deprecated: None,
-                        where_in: ParameterIn::Path,
-                    });
-                    match new_parameter_objects {
-                        Some(mut data_parameter) => {
-                            data_parameter.push(parmeter);
-                            new_parameter_objects = Some(data_parameter)
-                        }
-                        None => new_parameter_objects = Some(vec![parmeter]),
+        let mut operation = self.operation(method, &path).tag(tag);
+
+        // the given parameters describe the path ones better than the url does
+        merge_parameters(&mut operation.parameters, parameters.unwrap_or_default());
+
+        if let Some((title, request_body)) = request_body {
+            operation = operation.request_body(title, request_body);
+        }
+        for (status, (description, body)) in responses {
+            operation = operation.add_response(status, description, body);
+        }
+        if secure {
+            operation = operation.security("bearerAuth", &[]);
+        }
+
+        operation.finish()
+    }
+}
+
+/// Parses the parameters of a route template like `/users/{id:u64}/posts/:post`.
+///
+/// Returns the OpenAPI path, like `/users/{id}/posts/{post}`, and its path parameters.
+pub(crate) fn parse_path_template(
+    template: &str,
+) -> Result<(String, Vec<ParameterObjectOrReferenceObject>), SchemaError> {
+    let mut path = String::new();
+    let mut parameters = vec![];
+
+    for (i, segment) in template.split('/').enumerate() {
+        if i > 0 {
+            path.push('/');
+        }
+        if let Some(name) = segment.strip_prefix(':') {
+            path.push_str(&format!("{{{}}}", name));
+            parameters.push(get_path_parameter(name, None)?);
+            continue;
+        }
+
+        // `{...}` may be nested in a regex annotation like `{id:[0-9]{4}}`
+        let mut depth = 0;
+        let mut parameter = String::new();
+        for ch in segment.chars() {
+            match ch {
+                '{' if depth == 0 => depth += 1,
+                '}' if depth == 1 => {
+                    depth = 0;
+                    let (name, annotation) = match parameter.find(':') {
+                        Some(colon) => (&parameter[..colon], Some(&parameter[colon + 1..])),
+                        None => (parameter.as_str(), None),
                     };
+                    path.push_str(&format!("{{{}}}", name));
+                    parameters.push(get_path_parameter(name, annotation)?);
+                    parameter.clear();
+                }
+                _ if depth > 0 => {
+                    match ch {
+                        '{' => depth += 1,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    parameter.push(ch);
                 }
+                _ => path.push(ch),
             }
         }
+    }
 
-        let path_object = self.paths.get_mut(&path).unwrap();
-
-        let mut responses_per_http_status_codes = HashMap::new();
-        for (status_code, (description, title, mut value)) in responses {
-            let mut content_map = HashMap::new();
-
-            let mut new_schema;
-            if title == "String".to_string() {
-                new_schema = Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                    value.clone(),
-                )))
-            } else {
-                new_schema = Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                    json!({ "$ref": format!("#/components/schemas/{}", title) }),
-                )))
-            };
-
-            content_map.insert(
-                "application/json".to_owned(),
-                MediaTypeObject {
-                    // schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(value))),
-                    // use $ref components/schemas
-                    //  format!("#/components/schemas/{}", value.get("title").unwrap())
-                    schema: new_schema,
-                    example: None,
-                    examples: None,
-                    encoding: None,
-                },
-            );
-            responses_per_http_status_codes.insert(
-                status_code,
-                ResponseObjectOrReferenceObject::ResponseObject(Box::new(ResponseObject {
-                    description: description.to_owned(),
-                    headers: None,
-                    content: Some(content_map),
-                    links: None,
-                })),
-            );
+    Ok((path, parameters))
+}
+
+/// Documents the path parameter `name`, typed after its annotation like `u64` or `uuid`.
+///
+/// The annotations with regex metacharacters, like `[0-9]+`, are patterns the parameter matches,
+/// the other ones must name a known type.
+fn get_path_parameter(
+    name: &str,
+    annotation: Option<&str>,
+) -> Result<ParameterObjectOrReferenceObject, SchemaError> {
+    let schema = match annotation.map(str::trim) {
+        None | Some("") | Some("string") | Some("String") => String::get_json_schema_definition(),
+        Some("i8") => i8::get_json_schema_definition(),
+        Some("i16") => i16::get_json_schema_definition(),
+        Some("i32") => i32::get_json_schema_definition(),
+        Some("i64") => i64::get_json_schema_definition(),
+        Some("i128") => i128::get_json_schema_definition(),
+        Some("isize") => isize::get_json_schema_definition(),
+        Some("u8") => u8::get_json_schema_definition(),
+        Some("u16") => u16::get_json_schema_definition(),
+        Some("u32") => u32::get_json_schema_definition(),
+        Some("u64") => u64::get_json_schema_definition(),
+        Some("u128") => u128::get_json_schema_definition(),
+        Some("usize") => usize::get_json_schema_definition(),
+        Some("f32") => f32::get_json_schema_definition(),
+        Some("f64") => f64::get_json_schema_definition(),
+        Some("bool") => bool::get_json_schema_definition(),
+        Some("uuid") | Some("Uuid") => uuid::Uuid::get_json_schema_definition(),
+        Some(pattern) if pattern.contains(REGEX_METACHARACTERS) => json!({
+            "type": "string",
+            "pattern": format!("^{}$", pattern),
+        }),
+        Some(annotation) => {
+            return Err(SchemaError::UnknownPathType {
+                parameter: name.to_owned(),
+                annotation: annotation.to_owned(),
+            })
         }
+    };
 
-        let request_body = match request_body {
-            Some(mut rq) => {
-                // add $ref schemas
-                let mut comp_map = HashMap::new();
-                let mut new_schema;
-                if rq.0 == "String".to_string() {
-                    new_schema = Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                        json! ({
-                            "title": "sting",
-                            "type": "string"
-                        }),
-                    )))
-                } else {
-                    new_schema = Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
-                        json!({ "$ref": format!("#/components/schemas/{}", rq.0) }),
-                    )))
-                };
-                comp_map.insert(
-                    "application/json".to_owned(),
-                    MediaTypeObject {
-                        // schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(value))),
-                        // use $ref components/schemas
-                        //  format!("#/components/schemas/{}", value.get("title").unwrap())
-                        schema: new_schema,
-                        example: None,
-                        examples: None,
-                        encoding: None,
-                    },
-                );
-                rq.1.content = comp_map;
-                Some(RequestBodyObjectOrReferenceObject::RequestBodyObject(
-                    Box::new(rq.1),
-                ))
-            }
-            None => None,
-        };
-        let mut sec_map = HashMap::new();
-        sec_map.insert("bearerAuth".to_string(), json!([]));
-        
-
-        let operation_object = OperationObject {
-            responses: ResponsesObject {
-                default: None,
-                responses_per_http_status_codes: Some(responses_per_http_status_codes),
-            },
-            tags: Some(vec![tag.to_string()]),
-            summary: None,
+    Ok(ParameterObjectOrReferenceObject::ParameterObject(Box::new(
+        ParameterObject {
+            name: name.to_string(),
+            where_in: ParameterIn::Path,
             description: None,
-            external_docs: None,
-            operation_id: None,
-            // parameters,
-            parameters: new_parameter_objects,
-            request_body,
-            callbacks: None,
+            required: Some(true),
             deprecated: None,
-            security: if secure { Some(vec![sec_map]) } else { None },
-            servers: None,
-        };
+            allow_empty_value: None,
+            style: Some(ParameterIn::Path.default_style()),
+            explode: Some(ParameterIn::Path.default_style().default_explode()),
+            example: None,
+            schema: Some(SchemaObjectOrReferenceObject::SchemaObject(Box::new(
+                schema,
+            ))),
+        },
+    )))
+}
 
-        match method {
-            "GET" => path_object.get = Some(operation_object),
-            "POST" => path_object.post = Some(operation_object),
-            "PATCH" => path_object.patch = Some(operation_object),
-            "DELETE" => path_object.delete = Some(operation_object),
-            "PUT" => path_object.put = Some(operation_object),
-            _ => unimplemented!("Unknown method: Send a PR!"),
+const REGEX_METACHARACTERS: &[char] = &[
+    '\\', '^', '$', '.', '|', '?', '*', '+', '(', ')', '[', ']', '{', '}',
+];
+
+/// Appends `parameters` to `merged`, replacing the ones with the same name and location.
+pub(crate) fn merge_parameters(
+    merged: &mut Vec<ParameterObjectOrReferenceObject>,
+    parameters: Vec<ParameterObjectOrReferenceObject>,
+) {
+    for parameter in parameters {
+        let position = match &parameter {
+            ParameterObjectOrReferenceObject::ParameterObject(parameter) => {
+                merged.iter().position(|merged| match merged {
+                    ParameterObjectOrReferenceObject::ParameterObject(merged) => {
+                        merged.name == parameter.name && merged.where_in == parameter.where_in
+                    }
+                    ParameterObjectOrReferenceObject::ReferenceObject(_) => false,
+                })
+            }
+            ParameterObjectOrReferenceObject::ReferenceObject(_) => None,
+        };
+        match position {
+            Some(position) => merged[position] = parameter,
+            None => merged.push(parameter),
         }
     }
 }
@@ -6,14 +6,18 @@ extern crate proc_macro2;
 extern crate serde_json;
 #[macro_use]
 extern crate quote;
-use proc_macro2::{Ident, Punct, Spacing, Span, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
 use std::iter::FromIterator;
 
 extern crate struct2swagger;
 #[macro_use]
+extern crate serde;
+#[macro_use]
 extern crate struct2swagger_derive;
+#[macro_use]
+extern crate validator;
 
-use struct2swagger::JsonSchemaDefinition;
+use struct2swagger::{JsonSchemaDefinition, QueryDefinition};
 
 #[test]
 fn test_1() {
@@ -32,15 +36,11 @@ fn test_1() {
 
 #[test]
 fn test_2() {
-    let mut tokens = Vec::new();
-
-    tokens.push(TokenTree::Punct(Punct::new('(', Spacing::Alone)));
-    tokens.push(TokenTree::Punct(Punct::new('{', Spacing::Alone)));
-
-    tokens.push(TokenTree::Punct(Punct::new('}', Spacing::Alone)));
-    tokens.push(TokenTree::Punct(Punct::new(')', Spacing::Alone)));
-
-    let tokens = proc_macro2::TokenStream::from_iter(tokens.into_iter());
+    let object = TokenTree::Group(Group::new(Delimiter::Brace, TokenStream::new()));
+    let tokens = TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        TokenStream::from_iter(vec![object]),
+    ));
     let q = quote! {
         impl JsonSchemaDefinition for MyStructName {
             fn get_json_schema_definition() -> serde_json::Value {
@@ -50,7 +50,7 @@ fn test_2() {
     };
     let s = q.to_string();
 
-    assert_eq!(s, "impl JsonSchemaDefinition for MyStructName { fn get_json_schema_definition () -> serde_json :: Value { json ! ( { } ) ; } }");
+    assert_eq!(s, "impl JsonSchemaDefinition for MyStructName { fn get_json_schema_definition () -> serde_json :: Value { json ! ({ }) ; } }");
 }
 
 #[test]
@@ -82,6 +82,7 @@ fn simple_struct() {
         json!({
             "properties": {
                 "val1": {
+                    "format": "int32",
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
@@ -114,6 +115,7 @@ fn struct_with_arrays() {
                 "val1": {
                     "type": "array",
                     "items": {
+                        "format": "int32",
                         "maximum":255,
                         "minimum":0,
                         "type":"integer",
@@ -132,6 +134,7 @@ fn struct_with_arrays() {
                         "items": {
                             "type": "array",
                             "items": {
+                                "format": "int32",
                                 "maximum":255,
                                 "minimum":0,
                                 "type":"integer",
@@ -146,6 +149,51 @@ fn struct_with_arrays() {
     );
 }
 
+#[derive(Swagger)]
+struct StructWithCollections {
+    tags: std::collections::HashSet<String>,
+    ids: std::collections::BTreeSet<u8>,
+    rgb: [u8; 3],
+    queue: std::collections::VecDeque<String>,
+    names: Box<[String]>,
+}
+
+#[test]
+fn struct_with_collections() {
+    let t = StructWithCollections::get_json_schema_definition();
+
+    let byte = json!({ "type": "integer", "format": "int32", "minimum": 0, "maximum": 255 });
+    assert_eq!(
+        t["properties"],
+        json!({
+            "tags": {
+                "type": "array",
+                "items": { "type": "string" },
+                "uniqueItems": true,
+            },
+            "ids": {
+                "type": "array",
+                "items": byte,
+                "uniqueItems": true,
+            },
+            "rgb": {
+                "type": "array",
+                "items": byte,
+                "minItems": 3,
+                "maxItems": 3,
+            },
+            "queue": {
+                "type": "array",
+                "items": { "type": "string" },
+            },
+            "names": {
+                "type": "array",
+                "items": { "type": "string" },
+            },
+        })
+    );
+}
+
 #[derive(Swagger)]
 struct StructWithOption {
     val1: u8,
@@ -162,11 +210,13 @@ fn struct_with_option() {
         json!({
             "properties": {
                 "val1": {
+                    "format": "int32",
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
                 },
                 "val2": {
+                    "format": "int32",
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
@@ -181,7 +231,7 @@ fn struct_with_option() {
     );
 }
 
-#[derive(Swagger)]
+#[derive(Swagger, Serialize)]
 struct MySubStruct {
     val1: u8,
     val2: String,
@@ -203,6 +253,7 @@ fn struct_with_struct() {
         json!({
             "properties": {
                 "val1": {
+                    "format": "int32",
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
@@ -211,6 +262,7 @@ fn struct_with_struct() {
                     "type": "object",
                     "properties": {
                         "val1": {
+                            "format": "int32",
                             "maximum":255,
                             "minimum":0,
                             "type":"integer",
@@ -227,6 +279,7 @@ fn struct_with_struct() {
                         "type": "object",
                         "properties": {
                             "val1": {
+                                "format": "int32",
                                 "maximum":255,
                                 "minimum":0,
                                 "type":"integer",
@@ -260,6 +313,7 @@ fn struct_with_all_option() {
         json!({
             "properties": {
                 "val1": {
+                    "format": "int32",
                     "maximum":255,
                     "minimum":0,
                     "type":"integer",
@@ -288,3 +342,1081 @@ fn empty_struct() {
         })
     );
 }
+
+/// A documented struct.
+///
+/// With a second paragraph.
+#[derive(Swagger)]
+struct DocumentedStruct {
+    /// The first value
+    val1: u8,
+    val2: Option<String>,
+}
+
+#[test]
+fn documented_struct() {
+    let t = DocumentedStruct::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "description": "A documented struct.\n\nWith a second paragraph.",
+            "properties": {
+                "val1": {
+                    "description": "The first value",
+                    "format": "int32",
+                    "maximum":255,
+                    "minimum":0,
+                    "type":"integer",
+                },
+                "val2": {
+                    "type":"string",
+                },
+            },
+            "required":["val1"],
+            "type":"object",
+        })
+    );
+}
+
+#[test]
+fn documented_struct_query() {
+    let t = json!(DocumentedStruct::get_query_definitions());
+
+    assert_eq!(
+        t,
+        json!([
+            {
+                "name": "val1",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "description": "The first value",
+                "required": true,
+                "schema": <u8>::get_json_schema_definition(),
+            },
+            {
+                "name": "val2",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "required": false,
+                "schema": String::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[derive(Swagger)]
+struct StructWithAttributes {
+    #[swagger(
+        description = "The identifier",
+        example = 42,
+        format = "int32",
+        read_only
+    )]
+    id: u32,
+    #[swagger(rename = "displayName", example = "John")]
+    name: String,
+    #[swagger(deprecated, write_only)]
+    password: String,
+    #[swagger(default = 10)]
+    limit: u8,
+    #[swagger(skip)]
+    internal: Vec<u8>,
+}
+
+#[test]
+fn struct_with_attributes() {
+    let t = StructWithAttributes::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "id": {
+                    "description": "The identifier",
+                    "example": 42,
+                    "format": "int32",
+                    "readOnly": true,
+                    "maximum": 4_294_967_295u32,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "displayName": {
+                    "example": "John",
+                    "type": "string",
+                },
+                "password": {
+                    "deprecated": true,
+                    "writeOnly": true,
+                    "type": "string",
+                },
+                "limit": {
+                    "default": 10,
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+            },
+            "required": ["id", "displayName", "password"],
+            "type": "object",
+        })
+    );
+}
+
+#[test]
+fn struct_with_attributes_query() {
+    let t = json!(StructWithAttributes::get_query_definitions());
+
+    assert_eq!(
+        t,
+        json!([
+            {
+                "name": "id",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "description": "The identifier",
+                "required": true,
+                "example": 42,
+                "schema": {
+                    "format": "int32",
+                    "readOnly": true,
+                    "maximum": 4_294_967_295u32,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+            },
+            {
+                "name": "displayName",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "required": true,
+                "example": "John",
+                "schema": String::get_json_schema_definition(),
+            },
+            {
+                "name": "password",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "required": true,
+                "deprecated": true,
+                "schema": {
+                    "writeOnly": true,
+                    "type": "string",
+                },
+            },
+            {
+                "name": "limit",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "required": false,
+                "schema": {
+                    "default": 10,
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+            },
+        ])
+    );
+}
+
+#[derive(Swagger, Serialize)]
+struct Pagination {
+    page: u8,
+    per_page: Option<u8>,
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StructWithSerdeAttributes {
+    user_id: u32,
+    #[serde(rename = "type")]
+    kind: String,
+    r#ref: String,
+    #[serde(skip)]
+    internal: Vec<u8>,
+    #[serde(default)]
+    page_size: u8,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(flatten)]
+    inner: MySubStruct,
+    #[serde(flatten)]
+    optional_pagination: Option<Pagination>,
+}
+
+#[test]
+fn struct_with_serde_attributes() {
+    let t = StructWithSerdeAttributes::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "userId": {
+                    "format": "int64",
+                    "maximum": 4_294_967_295u32,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "type": {
+                    "type": "string",
+                },
+                "ref": {
+                    "type": "string",
+                },
+                "pageSize": {
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {
+                        "type": "string",
+                    },
+                },
+                "val1": {
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "val2": {
+                    "type": "string",
+                },
+                "page": {
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "per_page": {
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+            },
+            "required": ["userId", "type", "ref", "val1", "val2"],
+            "type": "object",
+        })
+    );
+}
+
+#[test]
+fn struct_with_serde_attributes_query() {
+    let t = json!(StructWithSerdeAttributes::get_query_definitions());
+    let names: Vec<(&str, bool)> = t
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            (
+                p["name"].as_str().unwrap(),
+                p["required"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        names,
+        vec![
+            ("userId", true),
+            ("type", true),
+            ("ref", true),
+            ("pageSize", false),
+            ("tags", false),
+            ("val1", true),
+            ("val2", true),
+            ("page", false),
+            ("per_page", false),
+        ]
+    );
+}
+
+#[derive(Swagger, Serialize)]
+struct StructWithFlattenedMap {
+    id: u32,
+    #[serde(flatten)]
+    extra: std::collections::HashMap<String, String>,
+}
+
+#[test]
+fn struct_with_flattened_map() {
+    let t = StructWithFlattenedMap::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "required": ["id"],
+            "properties": {
+                "id": <u32>::get_json_schema_definition(),
+            },
+            "additionalProperties": { "type": "string" },
+        })
+    );
+
+    let t = json!(StructWithFlattenedMap::get_query_definitions());
+    assert_eq!(t[1]["name"], "extra");
+    assert_eq!(t[1]["required"], false);
+    assert_eq!(
+        t[1]["schema"],
+        json!({
+            "type": "object",
+            "additionalProperties": { "type": "string" },
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(default, rename_all = "SCREAMING-KEBAB-CASE")]
+struct StructWithSerdeDefault {
+    first_value: u8,
+    second_value: String,
+}
+
+#[test]
+fn struct_with_serde_default() {
+    let t = StructWithSerdeDefault::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "properties": {
+                "FIRST-VALUE": {
+                    "format": "int32",
+                    "maximum": 255,
+                    "minimum": 0,
+                    "type": "integer",
+                },
+                "SECOND-VALUE": {
+                    "type": "string",
+                },
+            },
+            "type": "object",
+        })
+    );
+}
+
+/// A unit enum
+#[derive(Swagger, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum UnitEnum {
+    FirstValue,
+    SecondValue,
+    #[serde(rename = "third")]
+    Third,
+    #[serde(skip)]
+    Hidden,
+}
+
+#[test]
+fn unit_enum() {
+    let t = UnitEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "type": "string",
+            "description": "A unit enum",
+            "enum": ["first_value", "second_value", "third"],
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+enum ExternallyTaggedEnum {
+    A,
+    B,
+    /// A newtype variant
+    C(u8),
+    D(u8, String),
+    E {
+        val1: u8,
+        val2: Option<String>,
+    },
+}
+
+#[test]
+fn externally_tagged_enum() {
+    let t = ExternallyTaggedEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "type": "string",
+                    "enum": ["A", "B"],
+                },
+                {
+                    "type": "object",
+                    "description": "A newtype variant",
+                    "required": ["C"],
+                    "properties": {
+                        "C": <u8>::get_json_schema_definition(),
+                    },
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "required": ["D"],
+                    "properties": {
+                        "D": {
+                            "type": "array",
+                            "items": {
+                                "oneOf": [
+                                    <u8>::get_json_schema_definition(),
+                                    String::get_json_schema_definition(),
+                                ],
+                            },
+                            "minItems": 2,
+                            "maxItems": 2,
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+                {
+                    "type": "object",
+                    "required": ["E"],
+                    "properties": {
+                        "E": {
+                            "type": "object",
+                            "required": ["val1"],
+                            "properties": {
+                                "val1": <u8>::get_json_schema_definition(),
+                                "val2": String::get_json_schema_definition(),
+                            },
+                        },
+                    },
+                    "additionalProperties": false,
+                },
+            ],
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum InternallyTaggedEnum {
+    Empty,
+    Sub(MySubStruct),
+    #[serde(rename_all = "camelCase")]
+    Named {
+        first_value: u8,
+    },
+}
+
+#[test]
+fn internally_tagged_enum() {
+    let t = InternallyTaggedEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                { "$ref": "#/components/schemas/InternallyTaggedEnum_empty" },
+                { "$ref": "#/components/schemas/InternallyTaggedEnum_sub" },
+                { "$ref": "#/components/schemas/InternallyTaggedEnum_named" },
+            ],
+            "discriminator": {
+                "propertyName": "kind",
+                "mapping": {
+                    "empty": "#/components/schemas/InternallyTaggedEnum_empty",
+                    "sub": "#/components/schemas/InternallyTaggedEnum_sub",
+                    "named": "#/components/schemas/InternallyTaggedEnum_named",
+                },
+            },
+            "definitions": {
+                "InternallyTaggedEnum_empty": {
+                    "type": "object",
+                    "required": ["kind"],
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["empty"] },
+                    },
+                },
+                "InternallyTaggedEnum_sub": {
+                    "type": "object",
+                    "required": ["kind", "val1", "val2"],
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["sub"] },
+                        "val1": <u8>::get_json_schema_definition(),
+                        "val2": String::get_json_schema_definition(),
+                    },
+                },
+                "InternallyTaggedEnum_named": {
+                    "type": "object",
+                    "required": ["kind", "firstValue"],
+                    "properties": {
+                        "kind": { "type": "string", "enum": ["named"] },
+                        "firstValue": <u8>::get_json_schema_definition(),
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(tag = "type")]
+enum InternallyTaggedEnumWithoutNewtype {
+    A,
+    B { val1: u8 },
+}
+
+#[test]
+fn internally_tagged_enum_without_newtype() {
+    let t = InternallyTaggedEnumWithoutNewtype::get_json_schema_definition();
+
+    assert_eq!(
+        t["discriminator"],
+        json!({
+            "propertyName": "type",
+            "mapping": {
+                "A": "#/components/schemas/InternallyTaggedEnumWithoutNewtype_A",
+                "B": "#/components/schemas/InternallyTaggedEnumWithoutNewtype_B",
+            },
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(tag = "t", content = "c")]
+enum AdjacentlyTaggedEnum {
+    A,
+    B(u8),
+}
+
+#[test]
+fn adjacently_tagged_enum() {
+    let t = AdjacentlyTaggedEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                { "$ref": "#/components/schemas/AdjacentlyTaggedEnum_A" },
+                { "$ref": "#/components/schemas/AdjacentlyTaggedEnum_B" },
+            ],
+            "discriminator": {
+                "propertyName": "t",
+                "mapping": {
+                    "A": "#/components/schemas/AdjacentlyTaggedEnum_A",
+                    "B": "#/components/schemas/AdjacentlyTaggedEnum_B",
+                },
+            },
+            "definitions": {
+                "AdjacentlyTaggedEnum_A": {
+                    "type": "object",
+                    "required": ["t"],
+                    "properties": {
+                        "t": { "type": "string", "enum": ["A"] },
+                    },
+                },
+                "AdjacentlyTaggedEnum_B": {
+                    "type": "object",
+                    "required": ["t", "c"],
+                    "properties": {
+                        "t": { "type": "string", "enum": ["B"] },
+                        "c": <u8>::get_json_schema_definition(),
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[derive(Swagger, Serialize)]
+#[serde(untagged)]
+enum UntaggedEnum {
+    A,
+    B(String),
+    C { val1: u8 },
+}
+
+#[test]
+fn untagged_enum() {
+    let t = UntaggedEnum::get_json_schema_definition();
+
+    assert_eq!(
+        t,
+        json!({
+            "oneOf": [
+                {
+                    "enum": [null],
+                    "nullable": true,
+                },
+                String::get_json_schema_definition(),
+                {
+                    "type": "object",
+                    "required": ["val1"],
+                    "properties": {
+                        "val1": <u8>::get_json_schema_definition(),
+                    },
+                },
+            ],
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct StructWithEnumFilter {
+    status: Option<UnitEnum>,
+}
+
+#[test]
+fn struct_with_enum_filter_query() {
+    let t = json!(StructWithEnumFilter::get_query_definitions());
+
+    assert_eq!(
+        t,
+        json!([
+            {
+                "name": "status",
+                "in": "query",
+                "style": "form",
+                "explode": true,
+                "required": false,
+                "schema": UnitEnum::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+/// A user identifier
+#[derive(Swagger)]
+struct UserId(u64);
+
+#[derive(Swagger)]
+#[swagger(component)]
+struct Email(String);
+
+#[derive(Swagger)]
+struct Point(i32, i32, String);
+
+#[derive(Swagger)]
+struct Coordinates(f64, f64);
+
+#[derive(Swagger)]
+struct UnitStruct;
+
+#[derive(Swagger)]
+struct StructWithNewtypes {
+    id: UserId,
+    email: Option<Email>,
+}
+
+#[test]
+fn newtype_struct() {
+    assert_eq!(
+        UserId::get_json_schema_definition(),
+        json!({
+            "description": "A user identifier",
+            "format": "int64",
+            "minimum": 0,
+            "type": "integer",
+        })
+    );
+    assert_eq!(
+        Email::get_json_schema_definition(),
+        json!({
+            "$ref": "#/components/schemas/Email",
+            "definitions": {
+                "Email": {
+                    "title": "Email",
+                    "type": "string",
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn tuple_struct() {
+    assert_eq!(
+        Point::get_json_schema_definition(),
+        json!({
+            "type": "array",
+            "items": {
+                "oneOf": [
+                    <i32>::get_json_schema_definition(),
+                    String::get_json_schema_definition(),
+                ],
+            },
+            "minItems": 3,
+            "maxItems": 3,
+        })
+    );
+    assert_eq!(
+        Coordinates::get_json_schema_definition(),
+        json!({
+            "type": "array",
+            "items": <f64>::get_json_schema_definition(),
+            "minItems": 2,
+            "maxItems": 2,
+        })
+    );
+    assert_eq!(
+        UnitStruct::get_json_schema_definition(),
+        json!({
+            "enum": [null],
+            "nullable": true,
+        })
+    );
+}
+
+#[test]
+fn struct_with_newtypes() {
+    assert_eq!(
+        StructWithNewtypes::get_json_schema_definition(),
+        json!({
+            "properties": {
+                "id": UserId::get_json_schema_definition(),
+                "email": {
+                    "$ref": "#/components/schemas/Email",
+                    "definitions": {
+                        "Email": { "title": "Email", "type": "string" },
+                    },
+                },
+            },
+            "required": ["id"],
+            "type": "object",
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct Page<T> {
+    items: Vec<T>,
+    total: u64,
+}
+
+#[derive(Swagger)]
+#[swagger(component)]
+struct Pair<'a, A, B>
+where
+    A: Clone,
+{
+    name: &'a str,
+    first: A,
+    second: Option<B>,
+}
+
+#[derive(Swagger, Serialize)]
+struct GenericQuery<F> {
+    #[serde(flatten)]
+    filter: F,
+    limit: Option<u8>,
+}
+
+#[test]
+fn generic_struct() {
+    assert_eq!(
+        Page::<MySubStruct>::get_json_schema_definition(),
+        json!({
+            "properties": {
+                "items": {
+                    "type": "array",
+                    "items": MySubStruct::get_json_schema_definition(),
+                },
+                "total": <u64>::get_json_schema_definition(),
+            },
+            "required": ["items", "total"],
+            "type": "object",
+        })
+    );
+    assert_eq!(
+        Pair::<u8, String>::get_json_schema_definition(),
+        json!({
+            "$ref": "#/components/schemas/Pair_for_uint8_and_String",
+            "definitions": {
+                "Pair_for_uint8_and_String": {
+                    "title": "Pair_for_uint8_and_String",
+                    "properties": {
+                        "name": <&str>::get_json_schema_definition(),
+                        "first": <u8>::get_json_schema_definition(),
+                        "second": String::get_json_schema_definition(),
+                    },
+                    "required": ["name", "first"],
+                    "type": "object",
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn generic_struct_query() {
+    let t = json!(GenericQuery::<Pagination>::get_query_definitions());
+    let names: Vec<&str> = t
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+
+    assert_eq!(names, vec!["limit", "page", "per_page"]);
+}
+
+#[test]
+fn component_names() {
+    assert_eq!(MySubStruct::get_component_name(), "MySubStruct");
+    assert_eq!(
+        Page::<MySubStruct>::get_component_name(),
+        "Page_for_MySubStruct"
+    );
+    assert_eq!(
+        Page::<Page<u64>>::get_component_name(),
+        "Page_for_Page_for_uint64"
+    );
+    assert_eq!(
+        Page::<Vec<String>>::get_component_name(),
+        "Page_for_Array_of_String"
+    );
+    assert_eq!(
+        <Option<MySubStruct>>::get_component_name(),
+        "Nullable_MySubStruct"
+    );
+}
+
+#[derive(Swagger)]
+struct Constrained {
+    #[swagger(min = -5, max = 5, multiple_of = 0.5)]
+    score: f64,
+    #[swagger(min_length = 1, max_length = 20, pattern = "^[a-z]+$")]
+    name: String,
+    #[swagger(min_items = 1, max_items = 3, unique_items)]
+    tags: Vec<String>,
+}
+
+#[test]
+fn validation_attributes() {
+    assert_eq!(
+        Constrained::get_json_schema_definition(),
+        json!({
+            "type": "object",
+            "properties": {
+                "score": {
+                    "type": "number",
+                    "minimum": -5,
+                    "format": "double",
+                    "maximum": 5,
+                    "multipleOf": 0.5,
+                },
+                "name": {
+                    "type": "string",
+                    "minLength": 1,
+                    "maxLength": 20,
+                    "pattern": "^[a-z]+$",
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "minItems": 1,
+                    "maxItems": 3,
+                    "uniqueItems": true,
+                },
+            },
+            "required": ["score", "name", "tags"],
+        })
+    );
+}
+
+#[test]
+fn validation_attributes_query() {
+    let parameters = serde_json::to_value(Constrained::get_query_definitions()).unwrap();
+
+    assert_eq!(
+        parameters[1]["schema"],
+        json!({
+            "type": "string",
+            "minLength": 1,
+            "maxLength": 20,
+            "pattern": "^[a-z]+$",
+        })
+    );
+}
+
+#[derive(Swagger, Validate)]
+struct Validated {
+    #[validate(length(min = 1, max = 10))]
+    name: String,
+    #[validate(length(min = 1))]
+    tags: Vec<String>,
+    #[validate(range(min = 18, max = 150))]
+    age: u8,
+    #[validate(email)]
+    email: String,
+    #[validate(url)]
+    #[swagger(format = "url")]
+    website: Option<String>,
+}
+
+#[test]
+fn validate_attributes() {
+    assert_eq!(
+        Validated::get_json_schema_definition(),
+        json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1, "maxLength": 10 },
+                "tags": { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+                "age": { "type": "integer", "format": "int32", "minimum": 18, "maximum": 150 },
+                "email": { "type": "string", "format": "email" },
+                "website": { "type": "string", "format": "url" },
+            },
+            "required": ["name", "tags", "age", "email"],
+        })
+    );
+}
+
+#[derive(Swagger)]
+struct OperationInputs {
+    #[swagger(in = "path")]
+    id: Option<u64>,
+    #[swagger(in = "header", rename = "X-Request-Id")]
+    request_id: Option<String>,
+    #[swagger(in = "cookie")]
+    session: String,
+    #[swagger(in = "query")]
+    fields: Vec<String>,
+}
+
+#[test]
+fn parameter_locations() {
+    let t = json!(OperationInputs::get_query_definitions());
+
+    assert_eq!(
+        t,
+        json!([
+            {
+                "name": "id",
+                "in": "path",
+                "required": true,
+                "style": "simple",
+                "explode": false,
+                "schema": { "type": "integer", "format": "int64", "minimum": 0 },
+            },
+            {
+                "name": "X-Request-Id",
+                "in": "header",
+                "required": false,
+                "style": "simple",
+                "explode": false,
+                "schema": { "type": "string" },
+            },
+            {
+                "name": "session",
+                "in": "cookie",
+                "required": true,
+                "style": "form",
+                "explode": true,
+                "schema": { "type": "string" },
+            },
+            {
+                "name": "fields",
+                "in": "query",
+                "required": true,
+                "style": "form",
+                "explode": true,
+                "schema": { "type": "array", "items": { "type": "string" } },
+            },
+        ])
+    );
+}
+
+#[test]
+fn optional_path_parameters_stay_required() {
+    let t = json!(<Option<OperationInputs>>::get_query_definitions());
+    let required: Vec<_> = t
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["required"].as_bool().unwrap())
+        .collect();
+
+    assert_eq!(required, vec![true, false, false, false]);
+}
+
+#[derive(Swagger)]
+struct StructWithMaps {
+    labels: std::collections::HashMap<String, String>,
+    counts: std::collections::BTreeMap<u16, u64>,
+    by_kind: std::collections::HashMap<UnitEnum, Vec<String>>,
+}
+
+#[test]
+fn struct_with_maps() {
+    let t = StructWithMaps::get_json_schema_definition();
+
+    assert_eq!(
+        t["properties"],
+        json!({
+            "labels": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+            },
+            "counts": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "format": "int64",
+                },
+                "propertyNames": { "type": "string", "pattern": "^[0-9]+$" },
+            },
+            "by_kind": {
+                "type": "object",
+                "additionalProperties": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                },
+                "propertyNames": {
+                    "type": "string",
+                    "enum": ["first_value", "second_value", "third"],
+                },
+            },
+        })
+    );
+}
+
+/// A tree
+#[derive(Swagger)]
+struct Node {
+    value: std::rc::Rc<String>,
+    parent: Option<std::sync::Arc<Node>>,
+    first_child: Option<Box<Node>>,
+    children: Vec<Node>,
+}
+
+#[test]
+fn recursive_struct() {
+    let t = Node::get_json_schema_definition();
+
+    let node = json!({ "$ref": "#/components/schemas/Node" });
+    assert_eq!(
+        t,
+        json!({
+            "type": "object",
+            "description": "A tree",
+            "properties": {
+                "value": { "type": "string" },
+                "parent": node,
+                "first_child": node,
+                "children": { "type": "array", "items": node },
+            },
+            "required": ["value", "children"],
+        })
+    );
+}
@@ -1,5 +1,4 @@
 #![allow(dead_code)]
-
 use std::collections::HashMap;
 
 extern crate proc_macro;
@@ -12,10 +11,15 @@ extern crate struct2swagger;
 #[macro_use]
 extern crate struct2swagger_derive;
 
-use struct2swagger::swagger_object::SwaggerObject;
-use struct2swagger::{JsonSchemaDefinition, QueryDefinition};
+use struct2swagger::swagger_object::{
+    AuthorizationCodeOAuthFlowObject, ClientCredentialsOAuthFlowObject, HttpMethod,
+    OAuthFlowsObject, ResponseStatus, SchemaError, SecurityError, SecuritySchemeObject,
+    SwaggerObject, UnknownHttpMethod,
+};
+use struct2swagger::JsonSchemaDefinition;
+use struct2swagger::ParameterIn;
 
-use schemars::{schema::RootSchema, schema_for, schema_for_value, JsonSchema};
+use schemars::{schema_for, JsonSchema};
 use serde_json::value::Value;
 
 #[derive(Swagger, JsonSchema)]
@@ -35,11 +39,48 @@ const TITLE: &str = "the title";
 const VERSION: &str = "1.0.1";
 const DESCRIPTION: &str = "the description";
 
+fn security_schemes() -> Value {
+    json!({
+        "bearerAuth": {
+            "type": "http",
+            "description": "Bearer Authentication See RFC 6750",
+            "scheme": "bearer",
+            "bearerFormat": "JWT",
+        },
+    })
+}
+
+fn simple_struct_schema() -> Value {
+    json!({
+        "title": "SimpleStruct",
+        "type": "object",
+        "required": ["val1", "val2"],
+        "properties": {
+            "val1": { "type": "integer", "format": "uint8", "minimum": 0.0 },
+            "val2": { "type": "string" },
+        },
+    })
+}
+
+fn reference(title: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", title) })
+}
+
 #[test]
 fn with_response() {
-    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, None);
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
 
-    swagger_add_router!(swagger_object, "GET", "/", 200, DESCRIPTION, SimpleStruct);
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/",
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
 
     let stringified = serde_json::to_string(&swagger_object).unwrap();
     let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
@@ -50,17 +91,20 @@ fn with_response() {
             "openapi": "3.0.0",
             "info": {
                 "title": TITLE,
+                "description": DESCRIPTION,
                 "version": VERSION,
             },
+            "servers": [],
             "paths": {
                 "/": {
                     "get": {
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -69,7 +113,9 @@ fn with_response() {
                 },
             },
             "components": {
-                "schemas": {},
+                "schemas": {
+                    "SimpleStruct": simple_struct_schema(),
+                },
             },
         })
     );
@@ -77,10 +123,12 @@ fn with_response() {
 
 #[test]
 fn with_body() {
-    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, None);
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
 
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "POST",
         "/",
         "request_body",
@@ -88,7 +136,8 @@ fn with_body() {
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
 
     let stringified = serde_json::to_string(&swagger_object).unwrap();
     let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
@@ -99,25 +148,28 @@ fn with_body() {
             "openapi": "3.0.0",
             "info": {
                 "title": TITLE,
+                "description": DESCRIPTION,
                 "version": VERSION,
             },
+            "servers": [],
             "paths": {
                 "/": {
                     "post": {
                         "requestBody": {
                             "content": {
                                 "application/json": {
-                                    "schema": SimpleStruct::get_json_schema_definition(),
+                                    "schema": reference("SimpleStruct"),
                                 },
                             },
                             "required":true,
                         },
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -126,7 +178,9 @@ fn with_body() {
                 },
             },
             "components": {
-                "schemas": {},
+                "schemas": {
+                    "SimpleStruct": simple_struct_schema(),
+                },
             },
         })
     );
@@ -134,17 +188,20 @@ fn with_body() {
 
 #[test]
 fn with_query_string() {
-    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, None);
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
 
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "GET",
         "/",
         SimpleStruct,
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
 
     let stringified = serde_json::to_string(&swagger_object).unwrap();
     let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
@@ -155,8 +212,10 @@ fn with_query_string() {
             "openapi": "3.0.0",
             "info": {
                 "title": TITLE,
+                "description": DESCRIPTION,
                 "version": VERSION,
             },
+            "servers": [],
             "paths": {
                 "/": {
                     "get": {
@@ -165,21 +224,26 @@ fn with_query_string() {
                                 "name": "val1",
                                 "in": "query",
                                 "required": true,
+                                "style": "form",
+                                "explode": true,
                                 "schema": <u8>::get_json_schema_definition(),
                             },
                             {
                                 "name": "val2",
                                 "in": "query",
                                 "required": true,
+                                "style": "form",
+                                "explode": true,
                                 "schema": String::get_json_schema_definition(),
                             },
                         ],
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -188,19 +252,202 @@ fn with_query_string() {
                 },
             },
             "components": {
-                "schemas": {},
+                "schemas": {
+                    "SimpleStruct": simple_struct_schema(),
+                },
             },
         })
     );
 }
 
+#[derive(Swagger, JsonSchema)]
+struct UserQuery {
+    /// The user identifier
+    #[swagger(in = "path")]
+    id: u64,
+    fields: Option<String>,
+}
+
+#[test]
+fn with_path_and_query_parameters() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/users/{id}/{section}",
+        UserQuery,
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["paths"]["/users/{id}/{section}"]["get"]["parameters"],
+        json!([
+            {
+                "name": "id",
+                "in": "path",
+                "description": "The user identifier",
+                "required": true,
+                "style": "simple",
+                "explode": false,
+                "schema": <u64>::get_json_schema_definition(),
+            },
+            {
+                "name": "section",
+                "in": "path",
+                "required": true,
+                "style": "simple",
+                "explode": false,
+                "schema": { "type": "string" },
+            },
+            {
+                "name": "fields",
+                "in": "query",
+                "required": false,
+                "style": "form",
+                "explode": true,
+                "schema": String::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
+#[test]
+fn with_typed_path_template() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/users/{id:u64}/tokens/:token/{uuid:uuid}/{year:[0-9]{4}}",
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let path = "/users/{id}/tokens/{token}/{uuid}/{year}";
+    let parameters: Vec<_> = values["paths"][path]["get"]["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| (p["name"].as_str().unwrap(), p["schema"].clone()))
+        .collect();
+
+    assert_eq!(
+        parameters,
+        vec![
+            ("id", <u64>::get_json_schema_definition()),
+            ("token", String::get_json_schema_definition()),
+            ("uuid", json!({ "type": "string", "format": "uuid" })),
+            ("year", json!({ "type": "string", "pattern": "^[0-9]{4}$" })),
+        ]
+    );
+}
+
+#[test]
+fn unknown_path_types_are_errors() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    let error = SchemaError::UnknownPathType {
+        parameter: "id".to_owned(),
+        annotation: "u46".to_owned(),
+    };
+    assert_eq!(
+        swagger_add_router!(swagger_object, false, "tag", "GET", "/users/{id:u46}", [204 => ("Found")]),
+        Err(error.clone())
+    );
+    assert_eq!(
+        swagger_object
+            .operation(HttpMethod::Get, "/users/{id:u46}")
+            .empty_response(204, "Found")
+            .finish(),
+        Err(error.clone())
+    );
+    assert_eq!(
+        error.to_string(),
+        "unknown type `u46` of the path parameter `id`"
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert_eq!(values["paths"], json!({}));
+
+    swagger_object
+        .operation(HttpMethod::Get, "/users/{name:[a-z]+}")
+        .empty_response(204, "Found")
+        .finish()
+        .unwrap();
+}
+
+#[derive(Swagger, JsonSchema)]
+struct UserPath {
+    /// The user identifier
+    id: u64,
+}
+
+#[test]
+fn with_path_params_struct() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "DELETE",
+        "/users/{id}",
+        "path_params",
+        UserPath,
+        [204 => ("Deleted")]
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["paths"]["/users/{id}"]["delete"]["parameters"],
+        json!([
+            {
+                "name": "id",
+                "in": "path",
+                "description": "The user identifier",
+                "required": true,
+                "style": "simple",
+                "explode": false,
+                "schema": <u64>::get_json_schema_definition(),
+            },
+        ])
+    );
+}
+
 #[test]
 fn many_methods() {
-    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, None);
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
 
-    swagger_add_router!(swagger_object, "GET", "/", 200, DESCRIPTION, SimpleStruct);
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/",
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
         "POST",
         "/",
         "request_body",
@@ -208,9 +455,12 @@ fn many_methods() {
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "PATCH",
         "/",
         "request_body",
@@ -218,17 +468,23 @@ fn many_methods() {
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "DELETE",
         "/",
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "PUT",
         "/",
         "request_body",
@@ -236,7 +492,8 @@ fn many_methods() {
         200,
         DESCRIPTION,
         SimpleStruct
-    );
+    )
+    .unwrap();
 
     let stringified = serde_json::to_string(&swagger_object).unwrap();
     let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
@@ -247,25 +504,28 @@ fn many_methods() {
             "openapi": "3.0.0",
             "info": {
                 "title": TITLE,
+                "description": DESCRIPTION,
                 "version": VERSION,
             },
+            "servers": [],
             "paths": {
                 "/": {
                     "post": {
                         "requestBody": {
                             "content": {
                                 "application/json": {
-                                    "schema": SimpleStruct::get_json_schema_definition(),
+                                    "schema": reference("SimpleStruct"),
                                 },
                             },
                             "required":true,
                         },
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -275,17 +535,18 @@ fn many_methods() {
                         "requestBody": {
                             "content": {
                                 "application/json": {
-                                    "schema": SimpleStruct::get_json_schema_definition(),
+                                    "schema": reference("SimpleStruct"),
                                 },
                             },
                             "required":true,
                         },
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -295,41 +556,44 @@ fn many_methods() {
                         "requestBody": {
                             "content": {
                                 "application/json": {
-                                    "schema": SimpleStruct::get_json_schema_definition(),
+                                    "schema": reference("SimpleStruct"),
                                 },
                             },
                             "required":true,
                         },
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
                         },
                     },
                     "get": {
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
                         },
                     },
                     "delete": {
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleStruct::get_json_schema_definition(),
+                                        "schema": reference("SimpleStruct"),
                                     },
                                 },
                             },
@@ -338,7 +602,9 @@ fn many_methods() {
                 },
             },
             "components": {
-                "schemas": {},
+                "schemas": {
+                    "SimpleStruct": simple_struct_schema(),
+                },
             },
         })
     );
@@ -349,11 +615,15 @@ fn with_enum_body() {
     let mut swagger_object = SwaggerObject::new(
         TITLE,
         VERSION,
+        vec![],
+        DESCRIPTION,
         Some(vec![json!(&schema_for!(SimpleEnum).schema)]),
     );
 
     swagger_add_router!(
         swagger_object,
+        false,
+        "tag",
         "POST",
         "/",
         "request_body",
@@ -361,7 +631,8 @@ fn with_enum_body() {
         200,
         DESCRIPTION,
         SimpleEnum
-    );
+    )
+    .unwrap();
 
     let stringified = serde_json::to_string(&swagger_object).unwrap();
     let values: serde_json::Value = serde_json::from_str(&stringified).unwrap();
@@ -372,25 +643,28 @@ fn with_enum_body() {
             "openapi": "3.0.0",
             "info": {
                 "title": TITLE,
+                "description": DESCRIPTION,
                 "version": VERSION,
             },
+            "servers": [],
             "paths": {
                 "/": {
                     "post": {
                         "requestBody": {
                             "content": {
                                 "application/json": {
-                                    "schema": SimpleEnum::get_json_schema_definition(),
+                                    "schema": reference("SimpleEnum"),
                                 },
                             },
                             "required":true,
                         },
+                        "tags": ["tag"],
                         "responses": {
                             "200": {
                                 "description": DESCRIPTION,
                                 "content": {
                                     "application/json": {
-                                        "schema": SimpleEnum::get_json_schema_definition(),
+                                        "schema": reference("SimpleEnum"),
                                     },
                                 },
                             },
@@ -399,8 +673,1138 @@ fn with_enum_body() {
                 },
             },
             "components": {
-                "schemas": {},
+                "schemas": {
+                    "SimpleEnum": {
+                        "title": "SimpleEnum",
+                        "oneOf": [
+                            {
+                                "type": "string",
+                                "enum": ["A", "B"],
+                            },
+                            {
+                                "type": "object",
+                                "required": ["C"],
+                                "properties": {
+                                    "C": reference("SimpleStruct"),
+                                },
+                                "additionalProperties": false,
+                            },
+                        ],
+                    },
+                    "SimpleStruct": {
+                        "type": "object",
+                        "required": ["val1", "val2"],
+                        "properties": {
+                            "val1": { "type": "integer", "format": "uint8", "minimum": 0.0 },
+                            "val2": { "type": "string" },
+                        },
+                    },
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn head_options_and_trace() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(swagger_object, false, "tag", "head", "/", [200 => ("Exists")]).unwrap();
+    swagger_add_router!(swagger_object, false, "tag", "Options", "/", [204 => ("Allowed")])
+        .unwrap();
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "TRACE",
+        "/",
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let path_item = values["paths"]["/"].as_object().unwrap();
+    let mut methods: Vec<_> = path_item.keys().collect();
+    methods.sort();
+
+    assert_eq!(methods, vec!["head", "options", "trace"]);
+}
+
+#[test]
+fn http_methods_ignore_case() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(swagger_object, false, "tag", "gEt", "/", [204 => ("Found")]).unwrap();
+    swagger_add_router!(swagger_object, false, "tag", "pOST", "/", [204 => ("Created")]).unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let path_item = values["paths"]["/"].as_object().unwrap();
+    let mut methods: Vec<_> = path_item.keys().collect();
+    methods.sort();
+
+    assert_eq!(methods, vec!["get", "post"]);
+}
+
+#[test]
+fn parse_http_method() {
+    assert_eq!("get".parse(), Ok(HttpMethod::Get));
+    assert_eq!("Patch".parse(), Ok(HttpMethod::Patch));
+    assert_eq!("dElEtE".parse(), Ok(HttpMethod::Delete));
+    assert_eq!("OPTIONS".parse(), Ok(HttpMethod::Options));
+    assert_eq!(
+        "CONNECT".parse::<HttpMethod>(),
+        Err(UnknownHttpMethod("CONNECT".to_owned()))
+    );
+    assert_eq!(HttpMethod::Trace.to_string(), "TRACE");
+}
+
+#[derive(Swagger, JsonSchema)]
+struct ErrorBody {
+    message: String,
+}
+
+#[test]
+fn with_many_responses() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "POST",
+        "/",
+        "request_body",
+        SimpleStruct,
+        [
+            201 => ("Created", SimpleStruct),
+            400 => ("Bad request", ErrorBody),
+            422 => ("Unprocessable entity", ErrorBody),
+            default => ("Unexpected error", ErrorBody),
+        ]
+    )
+    .unwrap();
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "DELETE",
+        "/",
+        [204 => ("No content"), 404 => ("Not found", ErrorBody)]
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let error_content = json!({
+        "application/json": {
+            "schema": reference("ErrorBody"),
+        },
+    });
+
+    assert_eq!(
+        values["paths"]["/"]["post"]["responses"],
+        json!({
+            "201": {
+                "description": "Created",
+                "content": {
+                    "application/json": {
+                        "schema": reference("SimpleStruct"),
+                    },
+                },
+            },
+            "400": {
+                "description": "Bad request",
+                "content": error_content,
+            },
+            "422": {
+                "description": "Unprocessable entity",
+                "content": error_content,
+            },
+            "default": {
+                "description": "Unexpected error",
+                "content": error_content,
+            },
+        })
+    );
+    assert_eq!(
+        values["paths"]["/"]["delete"]["responses"],
+        json!({
+            "204": {
+                "description": "No content",
+            },
+            "404": {
+                "description": "Not found",
+                "content": error_content,
+            },
+        })
+    );
+}
+
+#[derive(Swagger, JsonSchema)]
+struct Pagination {
+    page: Option<u32>,
+}
+
+#[test]
+fn operation_builder() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object
+        .operation(HttpMethod::Post, "/users/{id}")
+        .summary("Update a user")
+        .description("Replaces the user")
+        .operation_id("updateUser")
+        .tag("users")
+        .tag("admin")
+        .path::<UserPath>()
+        .query::<Pagination>()
+        .body::<SimpleStruct>()
+        .response::<SimpleStruct>(200, "The updated user")
+        .empty_response(204, "Nothing changed")
+        .response::<ErrorBody>(ResponseStatus::Default, "Unexpected error")
+        .deprecated()
+        .security("oauth2", &["users:write"])
+        .finish()
+        .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["paths"]["/users/{id}"]["post"],
+        json!({
+            "summary": "Update a user",
+            "description": "Replaces the user",
+            "operationId": "updateUser",
+            "tags": ["users", "admin"],
+            "parameters": [
+                {
+                    "name": "id",
+                    "in": "path",
+                    "description": "The user identifier",
+                    "required": true,
+                    "style": "simple",
+                    "explode": false,
+                    "schema": <u64>::get_json_schema_definition(),
+                },
+                {
+                    "name": "page",
+                    "in": "query",
+                    "required": false,
+                    "style": "form",
+                    "explode": true,
+                    "schema": <u32>::get_json_schema_definition(),
+                },
+            ],
+            "requestBody": {
+                "content": {
+                    "application/json": {
+                        "schema": reference("SimpleStruct"),
+                    },
+                },
+                "required": true,
+            },
+            "responses": {
+                "200": {
+                    "description": "The updated user",
+                    "content": {
+                        "application/json": {
+                            "schema": reference("SimpleStruct"),
+                        },
+                    },
+                },
+                "204": {
+                    "description": "Nothing changed",
+                },
+                "default": {
+                    "description": "Unexpected error",
+                    "content": {
+                        "application/json": {
+                            "schema": reference("ErrorBody"),
+                        },
+                    },
+                },
+            },
+            "deprecated": true,
+            "security": [{ "oauth2": ["users:write"] }],
+        })
+    );
+}
+
+#[test]
+fn secure_route() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        true,
+        "tag",
+        "GET",
+        "/",
+        200,
+        DESCRIPTION,
+        SimpleStruct
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(values["components"]["securitySchemes"], security_schemes());
+    assert_eq!(
+        values["paths"]["/"]["get"]["security"],
+        json!([{ "bearerAuth": [] }])
+    );
+}
+
+#[test]
+fn security_schemes_and_requirements() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_security_scheme(
+        "apiKey",
+        SecuritySchemeObject::api_key("X-API-Key", ParameterIn::Header)
+            .description("For machine clients"),
+    );
+    swagger_object.add_security_scheme("basic", SecuritySchemeObject::http("basic"));
+    swagger_object.add_security_scheme(
+        "openId",
+        SecuritySchemeObject::open_id_connect("https://example.com/.well-known/openid"),
+    );
+    swagger_object.add_security("apiKey", &[]);
+    swagger_object.add_security("openId", &["profile"]);
+
+    swagger_object
+        .operation(HttpMethod::Get, "/health")
+        .without_security()
+        .empty_response(204, "Healthy")
+        .finish()
+        .unwrap();
+    swagger_object
+        .operation(HttpMethod::Get, "/me")
+        .security("basic", &[])
+        .response::<SimpleStruct>(200, DESCRIPTION)
+        .finish()
+        .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["securitySchemes"],
+        json!({
+            "apiKey": {
+                "type": "apiKey",
+                "description": "For machine clients",
+                "name": "X-API-Key",
+                "in": "header",
+            },
+            "basic": {
+                "type": "http",
+                "scheme": "basic",
+            },
+            "openId": {
+                "type": "openIdConnect",
+                "openIdConnectUrl": "https://example.com/.well-known/openid",
+            },
+        })
+    );
+    assert_eq!(
+        values["security"],
+        json!([{ "apiKey": [] }, { "openId": ["profile"] }])
+    );
+    assert_eq!(values["paths"]["/health"]["get"]["security"], json!([]));
+    assert_eq!(
+        values["paths"]["/me"]["get"]["security"],
+        json!([{ "basic": [] }])
+    );
+}
+
+fn scopes(scopes: &[(&str, &str)]) -> HashMap<String, String> {
+    scopes
+        .iter()
+        .map(|(scope, description)| (scope.to_string(), description.to_string()))
+        .collect()
+}
+
+fn oauth2_swagger_object() -> SwaggerObject {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_security_scheme(
+        "oauth2",
+        SecuritySchemeObject::oauth2(OAuthFlowsObject {
+            client_credentials: Some(ClientCredentialsOAuthFlowObject {
+                token_url: "https://example.com/token".to_owned(),
+                refresh_url: None,
+                scopes: scopes(&[("users:read", "Read the users")]),
+            }),
+            authorization_code: Some(AuthorizationCodeOAuthFlowObject {
+                authorization_url: "https://example.com/authorize".to_owned(),
+                token_url: "https://example.com/token".to_owned(),
+                refresh_url: Some("https://example.com/refresh".to_owned()),
+                scopes: scopes(&[("users:write", "Modify the users")]),
+            }),
+            ..OAuthFlowsObject::default()
+        }),
+    );
+    swagger_object.add_security_scheme("basic", SecuritySchemeObject::http("basic"));
+
+    swagger_object
+}
+
+#[test]
+fn oauth2_flows() {
+    let mut swagger_object = oauth2_swagger_object();
+    swagger_object
+        .operation(HttpMethod::Put, "/users")
+        .security("oauth2", &["users:read", "users:write"])
+        .empty_response(204, "Updated")
+        .finish()
+        .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["securitySchemes"]["oauth2"],
+        json!({
+            "type": "oauth2",
+            "flows": {
+                "clientCredentials": {
+                    "tokenUrl": "https://example.com/token",
+                    "scopes": { "users:read": "Read the users" },
+                },
+                "authorizationCode": {
+                    "authorizationUrl": "https://example.com/authorize",
+                    "tokenUrl": "https://example.com/token",
+                    "refreshUrl": "https://example.com/refresh",
+                    "scopes": { "users:write": "Modify the users" },
+                },
             },
         })
     );
+    assert_eq!(swagger_object.validate_security(), Ok(()));
+}
+
+#[test]
+fn validate_security_requirements() {
+    let mut swagger_object = oauth2_swagger_object();
+    swagger_object
+        .operation(HttpMethod::Get, "/users")
+        .security("oauth2", &["users:delete"])
+        .empty_response(204, "Done")
+        .finish()
+        .unwrap();
+
+    assert_eq!(
+        swagger_object.validate_security(),
+        Err(SecurityError::UnknownScope {
+            operation: "GET /users".to_owned(),
+            scheme: "oauth2".to_owned(),
+            scope: "users:delete".to_owned(),
+        })
+    );
+
+    let mut swagger_object = oauth2_swagger_object();
+    swagger_object.add_security("basic", &["users:read"]);
+
+    assert_eq!(
+        swagger_object.validate_security(),
+        Err(SecurityError::UnexpectedScopes {
+            operation: "the global security".to_owned(),
+            scheme: "basic".to_owned(),
+        })
+    );
+
+    let mut swagger_object = oauth2_swagger_object();
+    swagger_object.add_security("apiKey", &[]);
+
+    assert_eq!(
+        swagger_object.validate_security().unwrap_err().to_string(),
+        "the global security requires the unknown security scheme `apiKey`"
+    );
+}
+
+/// Links look like `#/definitions/Address`
+#[derive(JsonSchema)]
+struct Address {
+    street: String,
+}
+
+#[derive(JsonSchema)]
+struct Customer {
+    name: Option<String>,
+    address: Address,
+    previous_address: Option<Address>,
+}
+
+#[test]
+fn add_schema() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_schema::<Customer>().unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["schemas"],
+        json!({
+            "Customer": {
+                "title": "Customer",
+                "type": "object",
+                "required": ["address"],
+                "properties": {
+                    "name": { "type": "string", "nullable": true },
+                    "address": reference("Address"),
+                    "previous_address": {
+                        "allOf": [reference("Address")],
+                        "nullable": true,
+                    },
+                },
+            },
+            "Address": {
+                "description": "Links look like `#/definitions/Address`",
+                "type": "object",
+                "required": ["street"],
+                "properties": {
+                    "street": { "type": "string" },
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn schemas_are_converted_structurally() {
+    let swagger_object = SwaggerObject::new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![
+            json!(&schema_for!(Customer).schema),
+            json!(&schema_for!(Address).schema),
+        ]),
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["schemas"]["Customer"]["properties"],
+        json!({
+            "name": { "type": "string", "nullable": true },
+            "address": reference("Address"),
+            "previous_address": {
+                "allOf": [reference("Address")],
+                "nullable": true,
+            },
+        })
+    );
+    assert_eq!(
+        values["components"]["schemas"]["Address"]["description"],
+        "Links look like `#/definitions/Address`"
+    );
+}
+
+mod other {
+    use schemars::JsonSchema;
+
+    /// Another address
+    #[derive(JsonSchema)]
+    pub struct Address {
+        pub city: String,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct Account {
+        pub iban: String,
+    }
+
+    #[derive(JsonSchema)]
+    pub struct Supplier {
+        pub account: Account,
+        pub address: Address,
+    }
+}
+
+#[derive(JsonSchema)]
+struct Order {
+    customer: Customer,
+    items: Vec<Address>,
+}
+
+#[test]
+fn nested_definitions_are_hoisted() {
+    let swagger_object = SwaggerObject::new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![json!(schema_for!(Order))]),
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = values["components"]["schemas"].as_object().unwrap();
+
+    let mut names = schemas.keys().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["Address", "Customer", "Order"]);
+    assert_eq!(
+        schemas["Order"],
+        json!({
+            "title": "Order",
+            "type": "object",
+            "required": ["customer", "items"],
+            "properties": {
+                "customer": reference("Customer"),
+                "items": { "type": "array", "items": reference("Address") },
+            },
+        })
+    );
+    assert_eq!(
+        schemas["Customer"]["properties"]["address"],
+        reference("Address")
+    );
+}
+
+#[derive(JsonSchema)]
+struct Page<T> {
+    items: Vec<T>,
+    total: u64,
+}
+
+#[test]
+fn generic_and_path_types_are_bodies() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/customers",
+        200,
+        DESCRIPTION,
+        Page<Customer>
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let operation = &values["paths"]["/customers"]["get"];
+    assert_eq!(
+        operation["responses"]["200"]["content"]["application/json"]["schema"],
+        reference("Page_for_Customer")
+    );
+    assert_eq!(
+        values["components"]["schemas"]["Page_for_Customer"]["properties"]["items"],
+        json!({ "type": "array", "items": reference("Customer") })
+    );
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "POST",
+        "/suppliers",
+        "request_body",
+        other::Supplier,
+        [
+            201 => ("Created", Page<other::Supplier>),
+            default => ("Error", other::Account),
+        ]
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let operation = &values["paths"]["/suppliers"]["post"];
+    assert_eq!(
+        operation["requestBody"]["content"]["application/json"]["schema"],
+        reference("Supplier")
+    );
+    assert_eq!(
+        operation["responses"]["201"]["content"]["application/json"]["schema"],
+        reference("Page_for_Supplier")
+    );
+    assert_eq!(
+        operation["responses"]["default"]["content"]["application/json"]["schema"],
+        reference("Account")
+    );
+}
+
+#[test]
+fn conflicting_definitions_are_errors() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_schema::<Customer>().unwrap();
+    swagger_object.add_schema::<Order>().unwrap();
+    assert_eq!(
+        swagger_object.add_schema::<other::Supplier>(),
+        Err(SchemaError::Conflict("Address".to_owned()))
+    );
+    assert_eq!(
+        swagger_object.add_schema_value(json!(schema_for!(other::Supplier))),
+        Err(SchemaError::Conflict("Address".to_owned()))
+    );
+    assert_eq!(
+        swagger_object.add_schema_value(json!({ "type": "string" })),
+        Err(SchemaError::MissingTitle)
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = values["components"]["schemas"].as_object().unwrap();
+    let mut names = schemas.keys().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["Address", "Customer", "Order"]);
+    assert_eq!(
+        SchemaError::Conflict("Address".to_owned()).to_string(),
+        "two different schemas are named `Address`"
+    );
+}
+
+#[test]
+fn conflicting_definitions_fail_in_try_new() {
+    let swagger_object = SwaggerObject::try_new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![
+            json!(schema_for!(Customer)),
+            json!(schema_for!(other::Supplier)),
+        ]),
+    );
+    assert_eq!(
+        swagger_object.err(),
+        Some(SchemaError::Conflict("Address".to_owned()))
+    );
+
+    let swagger_object = SwaggerObject::try_new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![json!(schema_for!(Customer))]),
+    )
+    .unwrap();
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert_eq!(values["info"]["title"], TITLE);
+    assert_eq!(
+        values["components"]["schemas"]["Customer"]["title"],
+        "Customer"
+    );
+}
+
+#[test]
+#[should_panic(expected = "two different schemas are named `Address`")]
+fn conflicting_definitions_panic_in_new() {
+    SwaggerObject::new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![
+            json!(schema_for!(Customer)),
+            json!(schema_for!(other::Supplier)),
+        ]),
+    );
+}
+
+#[test]
+fn route_schemas_are_registered() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object
+        .operation(HttpMethod::Post, "/orders")
+        .body::<Order>()
+        .response::<Vec<Customer>>(200, "The customers")
+        .response::<String>(ResponseStatus::Default, "The error")
+        .finish()
+        .unwrap();
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "GET",
+        "/",
+        200,
+        DESCRIPTION,
+        Order
+    )
+    .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = values["components"]["schemas"].as_object().unwrap();
+
+    let mut names = schemas.keys().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["Address", "Array_of_Customer", "Customer", "Order"]
+    );
+    assert_eq!(schemas["Array_of_Customer"]["items"], reference("Customer"));
+    assert_eq!(
+        values["paths"]["/orders"]["post"]["responses"]["default"]["content"]["application/json"]
+            ["schema"],
+        json!({ "title": "String", "type": "string" })
+    );
+}
+
+#[derive(JsonSchema)]
+struct WithKeywordNames {
+    default: Option<Address>,
+    r#enum: Vec<Address>,
+}
+
+#[test]
+fn properties_named_like_keywords_are_converted() {
+    let swagger_object = SwaggerObject::new(
+        TITLE,
+        VERSION,
+        vec![],
+        DESCRIPTION,
+        Some(vec![json!(schema_for!(WithKeywordNames))]),
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["schemas"]["WithKeywordNames"]["properties"],
+        json!({
+            "default": {
+                "allOf": [reference("Address")],
+                "nullable": true,
+            },
+            "enum": {
+                "type": "array",
+                "items": reference("Address"),
+            },
+        })
+    );
+}
+
+#[derive(JsonSchema)]
+struct WithTuple {
+    pair: (u8, String),
+}
+
+#[test]
+fn tuple_items_are_converted() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object.add_schema::<WithTuple>().unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["schemas"]["WithTuple"]["properties"]["pair"],
+        json!({
+            "type": "array",
+            "items": {
+                "oneOf": [
+                    { "type": "integer", "format": "uint8", "minimum": 0.0 },
+                    { "type": "string" },
+                ],
+            },
+            "minItems": 2,
+            "maxItems": 2,
+        })
+    );
+}
+
+#[test]
+fn add_schema_and_routes_agree() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_schema::<Customer>().unwrap();
+    swagger_object
+        .operation(HttpMethod::Get, "/customer")
+        .response::<Customer>(200, "The customer")
+        .finish()
+        .unwrap();
+    swagger_add_router!(
+        swagger_object,
+        false,
+        "tag",
+        "POST",
+        "/customer",
+        "request_body",
+        Customer,
+        200,
+        DESCRIPTION,
+        Customer
+    )
+    .unwrap();
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object
+        .operation(HttpMethod::Get, "/customer")
+        .response::<Customer>(200, "The customer")
+        .finish()
+        .unwrap();
+    swagger_object.add_schema::<Customer>().unwrap();
+}
+
+fn default_payload() -> Value {
+    json!({ "id": 1 })
+}
+
+#[derive(JsonSchema)]
+struct Event {
+    #[schemars(example = "default_payload")]
+    payload: Value,
+}
+
+#[test]
+fn schemas_use_openapi3_keywords() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object.add_schema::<Event>().unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+
+    assert_eq!(
+        values["components"]["schemas"]["Event"]["properties"],
+        json!({
+            "payload": { "example": { "id": 1 } },
+        })
+    );
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+
+    swagger_object
+        .add_schema_value(json!(schema_for!(Event)))
+        .unwrap();
+    swagger_object.add_schema::<Event>().unwrap();
+    swagger_object
+        .add_schema_value(json!(schema_for!(Customer)))
+        .unwrap();
+    swagger_object.add_schema::<Customer>().unwrap();
+}
+
+#[test]
+fn conflicting_route_schemas_are_errors() {
+    use other::Supplier;
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object.add_schema::<Customer>().unwrap();
+
+    assert_eq!(
+        swagger_object
+            .operation(HttpMethod::Get, "/supplier")
+            .response::<other::Supplier>(200, "The supplier")
+            .finish(),
+        Err(SchemaError::Conflict("Address".to_owned()))
+    );
+    assert_eq!(
+        swagger_add_router!(
+            swagger_object,
+            false,
+            "tag",
+            "GET",
+            "/supplier",
+            200,
+            DESCRIPTION,
+            Supplier
+        ),
+        Err(SchemaError::Conflict("Address".to_owned()))
+    );
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert_eq!(values["paths"], json!({}));
+}
+
+#[derive(Swagger, JsonSchema)]
+struct Tree<T> {
+    value: T,
+    children: Vec<Tree<T>>,
+}
+
+#[derive(Swagger, JsonSchema)]
+#[serde(rename = "Labelled_{T}")]
+struct Labelled<T> {
+    label: String,
+    value: T,
+}
+
+#[test]
+fn component_names_match_schemars() {
+    fn assert_same_name<T: JsonSchemaDefinition + JsonSchema>() {
+        assert_eq!(T::get_component_name(), <T as JsonSchema>::schema_name());
+    }
+
+    assert_same_name::<SimpleStruct>();
+    assert_same_name::<Tree<u8>>();
+    assert_same_name::<Tree<Vec<Option<SimpleStruct>>>>();
+    assert_same_name::<Tree<HashMap<String, [i64; 2]>>>();
+    assert_same_name::<Tree<std::collections::BTreeSet<f32>>>();
+    assert_same_name::<Tree<Box<bool>>>();
+    assert_same_name::<Labelled<String>>();
+
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object.add_schema::<Tree<u8>>().unwrap();
+
+    let schema = Tree::<u8>::get_json_schema_definition();
+    assert_eq!(
+        schema["properties"]["children"]["items"],
+        reference("Tree_for_uint8")
+    );
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert!(values["components"]["schemas"]["Tree_for_uint8"].is_object());
+}
+
+#[derive(JsonSchema)]
+#[serde(tag = "kind")]
+enum Shape {
+    Circle { radius: f64 },
+    Square { side: f64 },
+}
+
+#[test]
+fn tagged_enum_variants_are_components() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object
+        .operation(HttpMethod::Post, "/shapes")
+        .body::<Shape>()
+        .empty_response(204, "Created")
+        .finish()
+        .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = &values["components"]["schemas"];
+    assert_eq!(
+        schemas["Shape"],
+        json!({
+            "title": "Shape",
+            "oneOf": [reference("Shape_Circle"), reference("Shape_Square")],
+            "discriminator": {
+                "propertyName": "kind",
+                "mapping": {
+                    "Circle": "#/components/schemas/Shape_Circle",
+                    "Square": "#/components/schemas/Shape_Square",
+                },
+            },
+        })
+    );
+    assert_eq!(
+        schemas["Shape_Circle"]["properties"]["kind"],
+        json!({ "type": "string", "enum": ["Circle"] })
+    );
+    assert_eq!(schemas["Shape_Square"]["required"], json!(["kind", "side"]));
+}
+
+#[derive(Swagger, serde::Serialize)]
+#[swagger(component)]
+#[serde(tag = "kind")]
+enum DerivedShape {
+    Circle { radius: f64 },
+    Square { side: f64 },
+}
+
+#[test]
+fn derived_tagged_enum_variants_are_components() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object.add_component::<DerivedShape>().unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let schemas = &values["components"]["schemas"];
+    assert_eq!(
+        schemas["DerivedShape"]["oneOf"],
+        json!([
+            reference("DerivedShape_Circle"),
+            reference("DerivedShape_Square"),
+        ])
+    );
+    assert_eq!(
+        schemas["DerivedShape"]["discriminator"]["mapping"]["Circle"],
+        "#/components/schemas/DerivedShape_Circle"
+    );
+    assert!(schemas["DerivedShape"].get("definitions").is_none());
+    assert_eq!(
+        schemas["DerivedShape_Circle"]["required"],
+        json!(["kind", "radius"])
+    );
+}
+
+/// An email address
+#[derive(Swagger, serde::Serialize)]
+#[swagger(component)]
+struct EmailAddress(String);
+
+#[derive(Swagger, serde::Serialize)]
+#[swagger(component)]
+struct Contact {
+    email: EmailAddress,
+    backup_email: Option<EmailAddress>,
+}
+
+#[derive(Swagger, serde::Serialize)]
+struct ContactQuery {
+    #[serde(flatten)]
+    contact: Contact,
+    verified: bool,
+}
+
+#[test]
+fn derived_components_are_referenced() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object.add_component::<Contact>().unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    assert_eq!(
+        values["components"]["schemas"],
+        json!({
+            "Contact": {
+                "title": "Contact",
+                "type": "object",
+                "required": ["email"],
+                "properties": {
+                    "email": reference("EmailAddress"),
+                    "backup_email": reference("EmailAddress"),
+                },
+            },
+            "EmailAddress": {
+                "title": "EmailAddress",
+                "description": "An email address",
+                "type": "string",
+            },
+        })
+    );
+}
+
+#[test]
+fn derived_components_of_parameters_are_registered() {
+    let mut swagger_object = SwaggerObject::new(TITLE, VERSION, vec![], DESCRIPTION, None);
+    swagger_object
+        .operation(HttpMethod::Get, "/contacts")
+        .query::<ContactQuery>()
+        .empty_response(204, "The contacts")
+        .finish()
+        .unwrap();
+
+    let values = serde_json::to_value(&swagger_object).unwrap();
+    let parameters = &values["paths"]["/contacts"]["get"]["parameters"];
+    assert_eq!(parameters[1]["name"], "email");
+    assert_eq!(parameters[1]["schema"], reference("EmailAddress"));
+    assert_eq!(
+        values["components"]["schemas"]["EmailAddress"]["type"],
+        "string"
+    );
+}
+
+#[test]
+fn flattened_derived_components_are_merged() {
+    let schema = ContactQuery::get_json_schema_definition();
+
+    assert_eq!(
+        schema["properties"],
+        json!({
+            "email": reference("EmailAddress"),
+            "backup_email": reference("EmailAddress"),
+            "verified": { "type": "boolean" },
+        })
+    );
+    assert_eq!(schema["required"], json!(["verified", "email"]));
+    assert_eq!(
+        schema["definitions"]["EmailAddress"]["title"],
+        "EmailAddress"
+    );
 }
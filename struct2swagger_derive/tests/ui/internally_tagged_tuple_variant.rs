@@ -0,0 +1,12 @@
+#[macro_use]
+extern crate serde;
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Serialize, Swagger)]
+#[serde(tag = "kind")]
+enum InternallyTaggedEnum {
+    A(u8, u8),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+#![deny(unused_must_use)]
+
+use struct2swagger::swagger_object::{HttpMethod, SwaggerObject};
+
+fn main() {
+    let mut swagger_object = SwaggerObject::new("title", "1.0.0", vec![], "description", None);
+
+    swagger_object
+        .operation(HttpMethod::Get, "/health")
+        .empty_response(204, "healthy");
+}
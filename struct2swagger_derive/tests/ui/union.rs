@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+union Union {
+    val1: u8,
+    val2: u16,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+#[swagger(schema)]
+struct StructWithUnknownAttribute {
+    val1: u8,
+}
+
+fn main() {}
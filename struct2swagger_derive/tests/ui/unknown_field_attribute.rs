@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+struct StructWithUnknownAttribute {
+    #[swagger(minimum = 1)]
+    val1: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger;
+
+use struct2swagger::swagger_object::SwaggerObject;
+
+fn main() {
+    let mut swagger_object = SwaggerObject::new("title", "1.0.0", vec![], "description", None);
+
+    swagger_add_router!(swagger_object, false, "tag", "CONNECT", "/", [200 => ("Connected")]);
+}
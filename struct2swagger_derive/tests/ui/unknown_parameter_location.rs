@@ -0,0 +1,10 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+struct Inputs {
+    #[swagger(in = "body")]
+    id: u64,
+}
+
+fn main() {}
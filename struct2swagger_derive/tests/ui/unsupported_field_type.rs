@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate struct2swagger_derive;
+
+#[derive(Swagger)]
+struct StructWithCallback {
+    callback: Option<fn(u8) -> u8>,
+}
+
+fn main() {}